@@ -0,0 +1,331 @@
+extern crate rand;
+extern crate crossbeam;
+
+use self::rand::{thread_rng, Rng};
+use self::crossbeam::{scope, ScopedJoinHandle};
+
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use candidate::{Candidate, compare};
+use context::{Context, HiveRng};
+use hive::{HiveBuilder, Hive, derive_rng};
+use result::{Result as AbcResult, Error as AbcError};
+
+/// How the islands of an [`Archipelago`](struct.Archipelago.html) are
+/// connected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// Each island sends its emigrants to the next island, and the last
+    /// island sends them to the first.
+    ///
+    /// This is the default.
+    Ring,
+
+    /// Each island sends its emigrants to every other island.
+    FullyConnected,
+
+    /// Each island sends its emigrants to another island, chosen at random
+    /// at each migration.
+    Random,
+}
+
+/// Runs several hives side by side, as islands that exchange candidates.
+///
+/// Islands search independently for a number of rounds, then each sends
+/// copies of its fittest candidates to its neighbours, where they replace
+/// the least fit working candidates. Isolation keeps the islands' searches
+/// diverse, while migration spreads good solutions among them.
+///
+/// The islands may be configured differently, for instance with different
+/// [scaling functions](scaling/index.html), as long as they share a type of
+/// [`Context`](trait.Context.html).
+///
+/// # Examples
+///
+/// ```
+/// # extern crate abc; fn main() {
+/// use abc::{HiveBuilder, Archipelago, Topology};
+/// use abc::real::RealVectorContext;
+///
+/// // Three islands searching the 5-dimensional sphere function.
+/// let islands = (0..3).map(|_| {
+///     let context = RealVectorContext::new(vec![(-5.12, 5.12); 5],
+///                                          |x: &[f64]| x.iter().map(|xi| xi * xi).sum());
+///     HiveBuilder::new(context, 10).set_threads(1)
+/// }).collect();
+///
+/// let archipelago = Archipelago::build(islands).unwrap()
+///                                              .set_topology(Topology::FullyConnected)
+///                                              .set_interval(5);
+/// let best = archipelago.run_for_rounds(50).unwrap();
+/// println!("{:?}", best.solution);
+/// # }
+/// ```
+pub struct Archipelago<Ctx: Context> {
+    islands: Vec<Hive<Ctx>>,
+    topology: Topology,
+    interval: usize,
+    migrants: usize,
+    rng: Mutex<HiveRng>,
+    rounds: AtomicUsize,
+}
+
+impl<Ctx: Context> Archipelago<Ctx> {
+    /// Gathers hives into an archipelago.
+    ///
+    /// By default, islands form a ring, and each island sends its single
+    /// fittest candidate on every 10 rounds.
+    pub fn new(islands: Vec<Hive<Ctx>>) -> Archipelago<Ctx> {
+        if islands.is_empty() {
+            panic!("An archipelago needs at least one island.");
+        }
+
+        Archipelago {
+            islands: islands,
+            topology: Topology::Ring,
+            interval: 10,
+            migrants: 1,
+            rng: Mutex::new(thread_rng().gen()),
+            rounds: AtomicUsize::new(0),
+        }
+    }
+
+    /// Builds a hive from each builder, and gathers them into an
+    /// archipelago.
+    pub fn build(builders: Vec<HiveBuilder<Ctx>>) -> AbcResult<Archipelago<Ctx>> {
+        if builders.is_empty() {
+            return Err(AbcError::InvalidConfiguration("an archipelago needs at least one \
+                                                       island"
+                                                          .to_string()));
+        }
+        let mut islands = Vec::with_capacity(builders.len());
+        for builder in builders {
+            islands.push(try!(builder.build()));
+        }
+        Ok(Archipelago::new(islands))
+    }
+
+    /// Sets how the islands are connected.
+    pub fn set_topology(mut self, topology: Topology) -> Archipelago<Ctx> {
+        self.topology = topology;
+        self
+    }
+
+    /// Sets the number of rounds that the islands run between migrations.
+    pub fn set_interval(mut self, rounds: usize) -> Archipelago<Ctx> {
+        if rounds == 0 {
+            panic!("Islands must run for at least one round between migrations.");
+        }
+        self.interval = rounds;
+        self
+    }
+
+    /// Sets the number of candidates that each island sends on at each
+    /// migration.
+    pub fn set_migrants(mut self, migrants: usize) -> Archipelago<Ctx> {
+        self.migrants = migrants;
+        self
+    }
+
+    /// Seeds the random choices of the [`Random`](enum.Topology.html#variant.Random)
+    /// topology.
+    ///
+    /// The islands' own generators are seeded separately, with
+    /// [`HiveBuilder::set_seed`](struct.HiveBuilder.html#method.set_seed).
+    pub fn set_seed(mut self, seed: u64) -> Archipelago<Ctx> {
+        self.rng = Mutex::new(derive_rng(seed, 0));
+        self
+    }
+
+    /// Returns the islands, in order.
+    pub fn islands(&self) -> &[Hive<Ctx>] {
+        &self.islands
+    }
+
+    /// Returns the best solution found on any island.
+    pub fn get(&self) -> AbcResult<Candidate<Ctx::Solution>> {
+        let mut best = try!(self.islands[0].get()).clone();
+        for island in &self.islands[1..] {
+            let guard = try!(island.get());
+            if compare(&guard, &best) == ::std::cmp::Ordering::Greater {
+                best = guard.clone();
+            }
+        }
+        Ok(best)
+    }
+
+    /// Chooses the islands that island `from` sends its emigrants to.
+    fn destinations(&self, from: usize, rng: &mut HiveRng) -> Vec<usize> {
+        let count = self.islands.len();
+        if count < 2 {
+            return Vec::new();
+        }
+        match self.topology {
+            Topology::Ring => vec![(from + 1) % count],
+            Topology::FullyConnected => (0..count).filter(|&to| to != from).collect(),
+            Topology::Random => vec![(from + rng.gen_range(1, count)) % count],
+        }
+    }
+
+    /// Sends each island's fittest candidates to its neighbours.
+    ///
+    /// Emigrants are all chosen before any of them arrive, so that the
+    /// order of the islands does not matter. This is called automatically by
+    /// [`run_for_rounds`](#method.run_for_rounds), but may also be called
+    /// directly.
+    pub fn migrate(&self) -> AbcResult<()> {
+        let mut arrivals = vec![Vec::new(); self.islands.len()];
+        {
+            let mut rng = try!(self.rng.lock());
+            for (from, island) in self.islands.iter().enumerate() {
+                let emigrants = try!(island.emigrants(self.migrants));
+                for to in self.destinations(from, &mut rng) {
+                    arrivals[to].extend(emigrants.iter().cloned());
+                }
+            }
+        }
+
+        for (island, mut immigrants) in self.islands.iter().zip(arrivals) {
+            // Let the fittest immigrants take the places of the least fit.
+            immigrants.sort_by(|a: &Candidate<Ctx::Solution>, b| compare(b, a));
+            try!(island.immigrate(&immigrants));
+        }
+        Ok(())
+    }
+
+    /// Runs every island for a fixed number of rounds, migrating between
+    /// them as it goes, then returns the best solution found.
+    ///
+    /// The islands run concurrently. Migrations take place whenever the
+    /// islands have run a multiple of the interval, counting rounds from
+    /// earlier runs. If any island fails, the others finish their stretch of
+    /// rounds, and the first error is returned.
+    pub fn run_for_rounds(&self, rounds: usize) -> AbcResult<Candidate<Ctx::Solution>> {
+        let mut remaining = rounds;
+        while remaining > 0 {
+            let until_migration = self.interval -
+                                  self.rounds.load(Ordering::SeqCst) % self.interval;
+            let stretch = until_migration.min(remaining);
+            try!(self.run_islands(stretch));
+            remaining -= stretch;
+
+            self.rounds.fetch_add(stretch, Ordering::SeqCst);
+            if stretch == until_migration {
+                try!(self.migrate());
+            }
+        }
+        self.get()
+    }
+
+    /// Runs every island for `rounds` rounds, each on its own thread.
+    fn run_islands(&self, rounds: usize) -> AbcResult<()> {
+        let mut handles: Vec<ScopedJoinHandle<AbcResult<()>>> = Vec::new();
+
+        scope(|scope| {
+            for island in &self.islands {
+                handles.push(scope.spawn(move || island.run_for_rounds(rounds).map(|_| ())));
+            }
+
+            // Join every island before reporting the first error.
+            let mut result = Ok(());
+            for handle in handles.drain(..) {
+                let joined = handle.join();
+                if result.is_ok() {
+                    result = joined;
+                }
+            }
+            result
+        })
+    }
+}
+
+impl<Ctx: Context> Debug for Archipelago<Ctx>
+    where Ctx::Solution: Debug
+{
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f,
+               "Archipelago {{ islands: {:?}, topology: {:?} }}",
+               self.islands,
+               self.topology)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::Walk;
+
+    fn islands(count: u64) -> Archipelago<Walk> {
+        Archipelago::build((0..count)
+                               .map(|seed| {
+                                   HiveBuilder::new(Walk, 5).set_threads(1).set_seed(seed)
+                               })
+                               .collect())
+            .unwrap()
+    }
+
+    #[test]
+    fn ring_passes_best_along() {
+        let archipelago = islands(3);
+        let before = archipelago.islands()
+                                .iter()
+                                .map(|island| island.get().unwrap().fitness)
+                                .collect::<Vec<f64>>();
+        archipelago.migrate().unwrap();
+        for (to, island) in archipelago.islands().iter().enumerate() {
+            let from = (to + 2) % 3;
+            assert!(island.get().unwrap().fitness >= before[from]);
+        }
+    }
+
+    #[test]
+    fn fully_connected_shares_best() {
+        let archipelago = islands(4).set_topology(Topology::FullyConnected).set_interval(5);
+        archipelago.run_for_rounds(10).unwrap();
+        let best = archipelago.get().unwrap().fitness;
+        for island in archipelago.islands() {
+            assert_eq!(island.get().unwrap().fitness, best);
+        }
+    }
+
+    #[test]
+    fn immigrants_replace_the_least_fit() {
+        let hive = HiveBuilder::new(Walk, 5).set_threads(1).set_seed(1).build().unwrap();
+        let mut fitnesses = hive.emigrants(5)
+                                .unwrap()
+                                .iter()
+                                .map(|c| c.fitness)
+                                .collect::<Vec<f64>>();
+        hive.immigrate(&[Candidate::new(1000, 1000.0), Candidate::new(-1000, -1000.0)]).unwrap();
+
+        fitnesses.pop();
+        fitnesses.insert(0, 1000.0);
+        let after = hive.emigrants(5).unwrap().iter().map(|c| c.fitness).collect::<Vec<f64>>();
+        assert_eq!(after, fitnesses);
+        assert_eq!(hive.get().unwrap().solution, 1000);
+    }
+
+    #[test]
+    fn migrations_follow_the_interval() {
+        // No walk climbs from below 100 to 1000 in ten rounds, so a solution
+        // beyond that must have come from the other island.
+        let archipelago = islands(2).set_interval(4);
+        let (first, second) = (&archipelago.islands()[0], &archipelago.islands()[1]);
+        first.immigrate(&[Candidate::new(1000, 1000.0)]).unwrap();
+
+        archipelago.run_for_rounds(3).unwrap();
+        assert!(second.get().unwrap().fitness < 1000.0);
+        archipelago.run_for_rounds(1).unwrap();
+        assert!(second.get().unwrap().fitness >= 1000.0);
+
+        // Seven rounds in, then a migration after the eighth.
+        first.immigrate(&[Candidate::new(5000, 5000.0)]).unwrap();
+        archipelago.run_for_rounds(3).unwrap();
+        assert!(second.get().unwrap().fitness < 5000.0);
+        archipelago.run_for_rounds(3).unwrap();
+        assert!(second.get().unwrap().fitness >= 5000.0);
+        assert_eq!(archipelago.rounds.load(Ordering::SeqCst), 10);
+    }
+}
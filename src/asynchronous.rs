@@ -0,0 +1,964 @@
+extern crate rand;
+
+use self::rand::{thread_rng, Rng};
+
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context as TaskContext, Poll, Wake, Waker};
+
+use candidate::{Candidate, WorkingCandidate, compare};
+use context::{ContextResult, HiveRng};
+use hive::{derive_rng, validate_weights, assess, choose};
+use objective::Objective;
+use result::{Result as AbcResult, Error as AbcError, catch_panic};
+use scaling::{ScalingFunction, proportionate};
+use selection::{Selection, roulette};
+use statistics::Statistics;
+use task::{TaskGenerator, Task};
+
+/// An evaluation in progress, which resolves to a solution's raw objective.
+pub type Evaluation = Pin<Box<Future<Output = ContextResult<f64>>>>;
+
+/// Context for generating solutions, and evaluating them asynchronously.
+///
+/// This is the counterpart of [`Context`](trait.Context.html) for an
+/// [`AsyncHive`](struct.AsyncHive.html). Making and exploring solutions are
+/// expected to be cheap, and happen on the hive's thread; only evaluations
+/// are asynchronous. Since the hive keeps evaluations in flight while it
+/// works on other solutions, the futures returned by `evaluate` must own
+/// everything they need.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate abc; extern crate rand; extern crate tokio; fn main() {
+/// use std::future;
+/// use rand::Rng;
+/// use abc::{AsyncContext, AsyncHiveBuilder, Candidate, Evaluation, HiveRng};
+///
+/// struct Remote;
+///
+/// impl AsyncContext for Remote {
+///     type Solution = i32;
+///
+///     fn make_with_rng(&self, rng: &mut HiveRng) -> i32 {
+///         rng.gen_range(0, 100)
+///     }
+///
+///     fn explore_with_rng(&self, field: &[Candidate<i32>], n: usize, rng: &mut HiveRng) -> i32 {
+///         field[n].solution + rng.gen_range(-10, 10)
+///     }
+///
+///     fn evaluate(&self, solution: &i32) -> Evaluation {
+///         // A real context would send a request here.
+///         Box::pin(future::ready(Ok(*solution as f64)))
+///     }
+/// }
+///
+/// // Any executor will do; from Rust 2018 on, the futures can be awaited.
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let builder = AsyncHiveBuilder::new(Remote, 10).set_concurrency(100);
+/// let mut hive = runtime.block_on(builder.build()).unwrap();
+/// let best = runtime.block_on(hive.run_for_rounds(20)).unwrap();
+/// println!("{:?}", best.solution);
+/// # }
+/// ```
+pub trait AsyncContext {
+    /// Type of solutions generated and evaluated by the ABC.
+    type Solution: Clone + Send + Sync + 'static;
+
+    /// Generates a brand new solution, drawing randomness from `rng`.
+    fn make_with_rng(&self, rng: &mut HiveRng) -> Self::Solution;
+
+    /// Looks "near" an existing solution, drawing randomness from `rng`.
+    ///
+    /// See [`Context::explore`](trait.Context.html#method.explore).
+    fn explore_with_rng(&self,
+                        field: &[Candidate<Self::Solution>],
+                        index: usize,
+                        rng: &mut HiveRng)
+                        -> Self::Solution;
+
+    /// Starts discovering the raw objective value of a solution.
+    ///
+    /// The value is converted to a fitness according to `direction`, as
+    /// with [`Context::evaluate_objective`](trait.Context.html#method.evaluate_objective).
+    /// If the evaluation fails, the run stops with
+    /// [`Error::User`](enum.Error.html#variant.User).
+    fn evaluate(&self, solution: &Self::Solution) -> Evaluation;
+
+    /// Direction in which the objective should be optimized.
+    ///
+    /// By default, this is `Objective::Maximize`.
+    fn direction(&self) -> Objective {
+        Objective::Maximize
+    }
+
+    /// Measures how far a solution is from satisfying the problem's
+    /// constraints.
+    ///
+    /// See [`Context::constraint_violation`](trait.Context.html#method.constraint_violation).
+    /// By default, every solution is feasible.
+    fn constraint_violation(&self, solution: &Self::Solution) -> f64 {
+        let _ = solution;
+        0.0
+    }
+}
+
+/// Manages the parameters of an [`AsyncHive`](struct.AsyncHive.html).
+pub struct AsyncHiveBuilder<Ctx: AsyncContext> {
+    workers: usize,
+    observers: usize,
+    retries: usize,
+    concurrency: Option<usize>,
+    context: Ctx,
+    scale: Box<ScalingFunction>,
+    selection: Box<Selection>,
+    nan_as_worst: bool,
+    seed: Option<u64>,
+}
+
+impl<Ctx: AsyncContext> AsyncHiveBuilder<Ctx> {
+    /// Creates a new hive.
+    ///
+    /// * `context` - Factory-like state that can be used while generating solutions.
+    /// * `workers` - Number of working solution candidates to maintain at a time.
+    pub fn new(context: Ctx, workers: usize) -> AsyncHiveBuilder<Ctx> {
+        if workers == 0 {
+            panic!("AsyncHiveBuilder must have at least one worker.");
+        }
+
+        AsyncHiveBuilder {
+            workers: workers,
+            observers: workers,
+            retries: workers,
+            concurrency: None,
+            context: context,
+            scale: proportionate(),
+            selection: roulette(),
+            nan_as_worst: false,
+            seed: None,
+        }
+    }
+
+    /// Sets the number of "bees" that will pick a candidate to work on at random.
+    ///
+    /// This defaults to the number of workers.
+    pub fn set_observers(mut self, observers: usize) -> AsyncHiveBuilder<Ctx> {
+        self.observers = observers;
+        self
+    }
+
+    /// Sets the number of times a candidate can go unimproved before being
+    /// reinitialized.
+    ///
+    /// This defaults to the number of workers.
+    pub fn set_retries(mut self, retries: usize) -> AsyncHiveBuilder<Ctx> {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the greatest number of evaluations that may be in flight at once.
+    ///
+    /// This defaults to the number of workers plus the number of observers,
+    /// which is one round's worth. Raising it lets more evaluations overlap,
+    /// at the cost of exploring from an older view of the field.
+    pub fn set_concurrency(mut self, concurrency: usize) -> AsyncHiveBuilder<Ctx> {
+        if concurrency == 0 {
+            panic!("AsyncHiveBuilder must allow at least one evaluation at a time.");
+        }
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    fn concurrency(&self) -> usize {
+        self.concurrency.unwrap_or(self.workers + self.observers)
+    }
+
+    /// Sets the scaling function for observers to use.
+    ///
+    /// See [`HiveBuilder::set_scaling`](struct.HiveBuilder.html#method.set_scaling).
+    pub fn set_scaling(mut self, scale: Box<ScalingFunction>) -> AsyncHiveBuilder<Ctx> {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the strategy that observers use to choose candidates.
+    ///
+    /// See [`HiveBuilder::set_selection`](struct.HiveBuilder.html#method.set_selection).
+    pub fn set_selection(mut self, selection: Box<Selection>) -> AsyncHiveBuilder<Ctx> {
+        self.selection = selection;
+        self
+    }
+
+    /// Sets whether NaN fitnesses are treated as the worst possible fitness.
+    ///
+    /// See [`HiveBuilder::set_nan_as_worst`](struct.HiveBuilder.html#method.set_nan_as_worst).
+    pub fn set_nan_as_worst(mut self, nan_as_worst: bool) -> AsyncHiveBuilder<Ctx> {
+        self.nan_as_worst = nan_as_worst;
+        self
+    }
+
+    /// Seeds the hive's random number generator.
+    ///
+    /// If the context's evaluations always complete in the same order, two
+    /// hives built with the same seed will produce identical sequences of
+    /// candidates.
+    pub fn set_seed(mut self, seed: u64) -> AsyncHiveBuilder<Ctx> {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Returns a future that makes and evaluates the initial candidates,
+    /// and resolves to a working hive.
+    pub fn build(self) -> Build<Ctx> {
+        let rng = match self.seed {
+            Some(seed) => derive_rng(seed, 0),
+            None => thread_rng().gen(),
+        };
+
+        Build {
+            field: vec![None; self.workers],
+            made: 0,
+            rng: rng,
+            evaluations: Evaluations::new(),
+            hive: Some(self),
+        }
+    }
+
+    /// Derives a candidate's fitness from its raw objective, and measures
+    /// its constraint violation.
+    fn candidate(&self,
+                 solution: Ctx::Solution,
+                 objective: f64)
+                 -> AbcResult<Candidate<Ctx::Solution>> {
+        assess(solution,
+               &[objective],
+               self.context.direction(),
+               self.nan_as_worst,
+               |solution| self.context.constraint_violation(solution))
+    }
+
+    /// Makes a new solution, and starts evaluating it.
+    fn make(&self,
+            job: Job,
+            evaluations: &mut Evaluations<Ctx::Solution>,
+            rng: &mut HiveRng)
+            -> AbcResult<()> {
+        let solution = try!(catch_panic("make", || self.context.make_with_rng(rng)));
+        self.start(job, solution, evaluations)
+    }
+
+    /// Starts evaluating a solution.
+    fn start(&self,
+             job: Job,
+             solution: Ctx::Solution,
+             evaluations: &mut Evaluations<Ctx::Solution>)
+             -> AbcResult<()> {
+        let evaluation = try!(catch_panic("evaluate", || self.context.evaluate(&solution)));
+        evaluations.push(job, solution, evaluation);
+        Ok(())
+    }
+}
+
+/// What a finished evaluation was for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Job {
+    /// A candidate for this place in the initial field.
+    Initial(usize),
+
+    /// A variant of the candidate at this place in the field.
+    Explored(usize),
+
+    /// A scout's replacement for the candidate at this place in the field.
+    Scout(usize),
+}
+
+/// Wakes the task that is polling the hive, when an evaluation can make
+/// progress.
+struct Signal {
+    woken: AtomicBool,
+    task: Arc<Mutex<Option<Waker>>>,
+}
+
+impl Wake for Signal {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        if let Ok(task) = self.task.lock() {
+            if let Some(ref waker) = *task {
+                waker.wake_by_ref();
+            }
+        }
+    }
+}
+
+/// An evaluation in flight.
+struct Pending<S> {
+    job: Job,
+    solution: S,
+    evaluation: Evaluation,
+    signal: Arc<Signal>,
+}
+
+/// Evaluations in flight, polled on behalf of whichever task is polling the
+/// hive.
+///
+/// Each evaluation has its own waker, so that only the evaluations that can
+/// make progress are polled. Waking any of them wakes the task.
+struct Evaluations<S> {
+    pending: Vec<Pending<S>>,
+    task: Arc<Mutex<Option<Waker>>>,
+}
+
+impl<S> Evaluations<S> {
+    fn new() -> Evaluations<S> {
+        Evaluations {
+            pending: Vec::new(),
+            task: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn push(&mut self, job: Job, solution: S, evaluation: Evaluation) {
+        self.pending.push(Pending {
+            job: job,
+            solution: solution,
+            evaluation: evaluation,
+            // Every evaluation is polled once when it starts.
+            signal: Arc::new(Signal {
+                woken: AtomicBool::new(true),
+                task: self.task.clone(),
+            }),
+        });
+    }
+
+    /// Polls the evaluations that have been woken, and returns the first to
+    /// finish, or `None` if none are in flight.
+    fn poll_next(&mut self, context: &mut TaskContext) -> Poll<Option<(Job, S, AbcResult<f64>)>> {
+        if self.pending.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        // The hive may be polled by a different task than last time.
+        {
+            let mut task = self.task.lock().unwrap_or_else(PoisonError::into_inner);
+            let current = match *task {
+                Some(ref waker) => waker.will_wake(context.waker()),
+                None => false,
+            };
+            if !current {
+                *task = Some(context.waker().clone());
+            }
+        }
+
+        for i in 0..self.pending.len() {
+            if !self.pending[i].signal.woken.swap(false, Ordering::SeqCst) {
+                continue;
+            }
+
+            let polled = {
+                let pending = &mut self.pending[i];
+                let waker = Waker::from(pending.signal.clone());
+                let mut context = TaskContext::from_waker(&waker);
+                let evaluation = &mut pending.evaluation;
+                catch_panic("evaluate", || evaluation.as_mut().poll(&mut context))
+            };
+            let result = match polled {
+                Ok(Poll::Pending) => continue,
+                Ok(Poll::Ready(result)) => result.map_err(AbcError::User),
+                Err(error) => Err(error),
+            };
+            let finished = self.pending.swap_remove(i);
+            return Poll::Ready(Some((finished.job, finished.solution, result)));
+        }
+        Poll::Pending
+    }
+}
+
+/// A hive that is evaluating its initial candidates.
+///
+/// This future is returned by
+/// [`AsyncHiveBuilder::build`](struct.AsyncHiveBuilder.html#method.build),
+/// and resolves to the working hive once every candidate has been evaluated.
+pub struct Build<Ctx: AsyncContext> {
+    hive: Option<AsyncHiveBuilder<Ctx>>,
+    field: Vec<Option<Candidate<Ctx::Solution>>>,
+    made: usize,
+    rng: HiveRng,
+    evaluations: Evaluations<Ctx::Solution>,
+}
+
+// Nothing in a build is pinned: the evaluations are boxed.
+impl<Ctx: AsyncContext> Unpin for Build<Ctx> {}
+
+impl<Ctx: AsyncContext> Build<Ctx> {
+    /// Makes and evaluates candidates, returning `true` once they are all
+    /// done, or `false` if it has to wait for an evaluation.
+    fn advance(&mut self, context: &mut TaskContext) -> AbcResult<bool> {
+        let hive = self.hive.as_ref().expect("The hive has already been built.");
+        loop {
+            while self.made < hive.workers && self.evaluations.len() < hive.concurrency() {
+                try!(hive.make(Job::Initial(self.made), &mut self.evaluations, &mut self.rng));
+                self.made += 1;
+            }
+            match self.evaluations.poll_next(context) {
+                Poll::Ready(Some((Job::Initial(n), solution, objective))) => {
+                    self.field[n] = Some(try!(hive.candidate(solution, try!(objective))));
+                }
+                Poll::Ready(Some(_)) => unreachable!(),
+                Poll::Ready(None) => return Ok(true),
+                Poll::Pending => return Ok(false),
+            }
+        }
+    }
+
+    /// Gathers the evaluated candidates into a working hive.
+    fn finish(&mut self) -> AsyncHive<Ctx> {
+        let hive = self.hive.take().unwrap();
+        let field = self.field.drain(..).map(Option::unwrap).collect::<Vec<_>>();
+        let best = field.iter()
+                        .fold(&field[0], |best, next| {
+                            if compare(next, best) == ::std::cmp::Ordering::Greater {
+                                next
+                            } else {
+                                best
+                            }
+                        })
+                        .clone();
+        let working = field.into_iter()
+                           .map(|candidate| WorkingCandidate::new(candidate, hive.retries))
+                           .collect();
+
+        AsyncHive {
+            working: working,
+            best: best,
+            scouting: BTreeSet::new(),
+            selections: Vec::new(),
+            rng: self.rng.clone(),
+            evaluations: hive.workers,
+            rounds: 0,
+            hive: hive,
+        }
+    }
+}
+
+impl<Ctx: AsyncContext> Future for Build<Ctx> {
+    type Output = AbcResult<AsyncHive<Ctx>>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut TaskContext) -> Poll<Self::Output> {
+        let build = self.get_mut();
+        match build.advance(context) {
+            Ok(true) => Poll::Ready(Ok(build.finish())),
+            Ok(false) => Poll::Pending,
+            Err(error) => Poll::Ready(Err(error)),
+        }
+    }
+}
+
+/// Runs the ABC algorithm with asynchronous evaluations.
+///
+/// A [`Hive`](struct.Hive.html) can have no more evaluations in flight than
+/// it has threads. When evaluating a solution means waiting on something
+/// else, such as a remote simulator, an `AsyncHive` does better. The hive
+/// follows the same employed, onlooker and scout phases as an asynchronous
+/// [`Hive`](struct.Hive.html), but rather than spreading the work across
+/// threads, it starts each task's evaluation as a future, and moves on to the
+/// next task while it is in flight. Each variant is compared with its
+/// candidate as soon as its evaluation finishes, and exhausted candidates are
+/// scouted at once.
+///
+/// Building and running the hive return futures, which do the hive's work
+/// as they are polled, so the caller's executor drives them; from Rust 2018
+/// on, they can simply be awaited. Since evaluations need not be `Send`,
+/// neither are these futures, so they run on single-threaded executors, such
+/// as tokio's `block_on` or `LocalSet`.
+pub struct AsyncHive<Ctx: AsyncContext> {
+    hive: AsyncHiveBuilder<Ctx>,
+    working: Vec<WorkingCandidate<Ctx::Solution>>,
+    best: Candidate<Ctx::Solution>,
+    scouting: BTreeSet<usize>,
+    selections: Vec<usize>,
+    rng: HiveRng,
+    evaluations: usize,
+    rounds: usize,
+}
+
+impl<Ctx: AsyncContext> AsyncHive<Ctx> {
+    /// Returns the best solution found by the hive.
+    pub fn get(&self) -> &Candidate<Ctx::Solution> {
+        &self.best
+    }
+
+    /// Returns the hive's context.
+    pub fn context(&self) -> &Ctx {
+        &self.hive.context
+    }
+
+    /// Returns the number of fitness evaluations started so far.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    /// Returns running totals describing the work the hive has done.
+    pub fn statistics(&self) -> Statistics {
+        Statistics {
+            rounds: self.rounds,
+            evaluations: self.evaluations,
+            cache_hits: 0,
+            cache_misses: 0,
+        }
+    }
+
+    /// Returns a future that runs for a fixed number of rounds, then
+    /// resolves to the best solution found.
+    ///
+    /// As with a [`Hive`](struct.Hive.html), a round ends once all of its
+    /// tasks have been started, and the run ends once every evaluation in
+    /// flight has finished.
+    pub fn run_for_rounds<'a>(&'a mut self, rounds: usize) -> Run<'a, Ctx> {
+        let mut tasks = TaskGenerator::new(self.hive.workers, self.hive.observers)
+                            .max_rounds(rounds);
+        if rounds == 0 {
+            tasks.stop();
+        }
+        self.run(tasks, usize::MAX)
+    }
+
+    /// Returns a future that runs for a fixed number of fitness evaluations,
+    /// then resolves to the best solution found.
+    pub fn run_for_evaluations<'a>(&'a mut self, evaluations: usize) -> Run<'a, Ctx> {
+        let budget = self.evaluations.saturating_add(evaluations);
+        let tasks = TaskGenerator::new(self.hive.workers, self.hive.observers);
+        self.run(tasks, budget)
+    }
+
+    fn run<'a>(&'a mut self, tasks: TaskGenerator, budget: usize) -> Run<'a, Ctx> {
+        Run {
+            rounds: self.rounds,
+            hive: self,
+            tasks: tasks,
+            budget: budget,
+            evaluations: Evaluations::new(),
+        }
+    }
+
+    /// Copies the working candidates.
+    fn field(&self) -> Vec<Candidate<Ctx::Solution>> {
+        self.working
+            .iter()
+            .map(|working| working.candidate.clone())
+            .collect()
+    }
+
+    /// Handles a finished evaluation.
+    fn finish(&mut self,
+              job: Job,
+              candidate: Candidate<Ctx::Solution>,
+              budget: usize,
+              evaluations: &mut Evaluations<Ctx::Solution>)
+              -> AbcResult<()> {
+        match job {
+            Job::Explored(n) => try!(self.apply(n, candidate, budget, evaluations)),
+            Job::Scout(n) => {
+                self.consider_improvement(&candidate);
+                self.working[n] = WorkingCandidate::new(candidate, self.hive.retries);
+                self.scouting.remove(&n);
+            }
+            Job::Initial(_) => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Replaces the candidate at `n` with `variant` if it is an improvement,
+    /// and otherwise depletes the candidate, scouting if it is exhausted.
+    fn apply(&mut self,
+             n: usize,
+             variant: Candidate<Ctx::Solution>,
+             budget: usize,
+             evaluations: &mut Evaluations<Ctx::Solution>)
+             -> AbcResult<()> {
+        // A scout is already on its way to replace this candidate.
+        if self.scouting.contains(&n) {
+            return Ok(());
+        }
+
+        if compare(&variant, &self.working[n].candidate) == ::std::cmp::Ordering::Greater {
+            self.consider_improvement(&variant);
+            self.working[n] = WorkingCandidate::new(variant, self.hive.retries);
+            return Ok(());
+        }
+
+        self.working[n].deplete();
+        if self.working[n].expired() && self.evaluations < budget {
+            self.scouting.insert(n);
+            try!(self.hive.make(Job::Scout(n), evaluations, &mut self.rng));
+            self.evaluations += 1;
+        }
+        Ok(())
+    }
+
+    fn consider_improvement(&mut self, candidate: &Candidate<Ctx::Solution>) {
+        if compare(candidate, &self.best) == ::std::cmp::Ordering::Greater {
+            self.best = candidate.clone();
+        }
+    }
+
+    /// Chooses a candidate for an observer, as a [`Hive`](struct.Hive.html)
+    /// does.
+    fn choose(&mut self, field: &[Candidate<Ctx::Solution>]) -> AbcResult<usize> {
+        let scale = &self.hive.scale;
+        let weights = || {
+            validate_weights(scale(field.iter().map(|candidate| candidate.fitness).collect()))
+        };
+        choose(&mut self.selections,
+               field,
+               weights,
+               &self.scouting,
+               &*self.hive.selection,
+               self.hive.observers,
+               &mut self.rng)
+    }
+}
+
+/// A run of an [`AsyncHive`](struct.AsyncHive.html), which resolves to the
+/// best solution found.
+///
+/// This future is returned by the hive's `run` methods. Dropping it before
+/// it finishes abandons the evaluations in flight, and the candidates that
+/// were being scouted keep their places until a later run scouts them again.
+pub struct Run<'a, Ctx: AsyncContext + 'a> {
+    hive: &'a mut AsyncHive<Ctx>,
+    tasks: TaskGenerator,
+    budget: usize,
+    // Rounds that the hive had run before this run began.
+    rounds: usize,
+    evaluations: Evaluations<Ctx::Solution>,
+}
+
+// Nothing in a run is pinned: the evaluations are boxed.
+impl<'a, Ctx: AsyncContext> Unpin for Run<'a, Ctx> {}
+
+impl<'a, Ctx: AsyncContext> Run<'a, Ctx> {
+    /// Keeps the hive's evaluations in flight, and handles each one as it
+    /// finishes, returning `true` once the run is over, or `false` if it has
+    /// to wait for an evaluation.
+    fn advance(&mut self, context: &mut TaskContext) -> AbcResult<bool> {
+        loop {
+            try!(self.start_tasks());
+            let (job, solution, objective) = match self.evaluations.poll_next(context) {
+                Poll::Ready(Some(finished)) => finished,
+                Poll::Ready(None) => return Ok(true),
+                Poll::Pending => return Ok(false),
+            };
+            let candidate = try!(self.hive.hive.candidate(solution, try!(objective)));
+            try!(self.hive.finish(job, candidate, self.budget, &mut self.evaluations));
+        }
+    }
+
+    /// Starts evaluating tasks' variants until the hive's concurrency is
+    /// reached, the tasks run out, or the budget is spent.
+    fn start_tasks(&mut self) -> AbcResult<()> {
+        let hive = &mut *self.hive;
+        while self.evaluations.len() < hive.hive.concurrency() && hive.evaluations < self.budget {
+            // Wait for the scouts if there is nothing else to work on.
+            if hive.scouting.len() == hive.working.len() {
+                break;
+            }
+
+            let task = self.tasks.next();
+            hive.rounds = self.rounds + self.tasks.round;
+            let field = hive.field();
+            let n = match task {
+                Some(Task::Worker(n)) => n,
+                Some(Task::Observer(_)) => try!(hive.choose(&field)),
+                None => break,
+            };
+            // Leave candidates that are being replaced alone.
+            if hive.scouting.contains(&n) {
+                continue;
+            }
+
+            let solution = {
+                let (context, rng) = (&hive.hive.context, &mut hive.rng);
+                try!(catch_panic("explore", || context.explore_with_rng(&field, n, rng)))
+            };
+            try!(hive.hive.start(Job::Explored(n), solution, &mut self.evaluations));
+            hive.evaluations += 1;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, Ctx: AsyncContext> Future for Run<'a, Ctx> {
+    type Output = AbcResult<Candidate<Ctx::Solution>>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut TaskContext) -> Poll<Self::Output> {
+        let run = self.get_mut();
+        match run.advance(context) {
+            Ok(true) => Poll::Ready(Ok(run.hive.best.clone())),
+            Ok(false) => Poll::Pending,
+            Err(error) => Poll::Ready(Err(error)),
+        }
+    }
+}
+
+impl<'a, Ctx: AsyncContext> Drop for Run<'a, Ctx> {
+    fn drop(&mut self) {
+        // No scout outlives the run that sent it, however the run ended.
+        self.hive.scouting.clear();
+    }
+}
+
+impl<Ctx: AsyncContext> Debug for AsyncHive<Ctx>
+    where Ctx::Solution: Debug
+{
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for working in &self.working {
+            try!(write!(f, "..{:?}..\n", working.candidate));
+        }
+        write!(f, ">>{:?}<<", self.best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::cmp::max;
+    use std::rc::Rc;
+    use std::thread;
+    use std::time::Duration;
+    use tokio::runtime::Builder;
+
+    /// Drives a future to completion on a single-threaded runtime.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    /// Resolves to a value once a timer thread wakes it.
+    struct Delayed {
+        value: f64,
+        done: Arc<AtomicBool>,
+        started: bool,
+        in_flight: Rc<Cell<usize>>,
+        peak: Rc<Cell<usize>>,
+    }
+
+    impl Future for Delayed {
+        type Output = ContextResult<f64>;
+
+        fn poll(mut self: Pin<&mut Self>, context: &mut TaskContext) -> Poll<ContextResult<f64>> {
+            if !self.started {
+                self.started = true;
+                let in_flight = self.in_flight.get() + 1;
+                self.in_flight.set(in_flight);
+                self.peak.set(max(self.peak.get(), in_flight));
+
+                let (done, waker) = (self.done.clone(), context.waker().clone());
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(5));
+                    done.store(true, Ordering::SeqCst);
+                    waker.wake();
+                });
+            }
+            if self.done.load(Ordering::SeqCst) {
+                self.in_flight.set(self.in_flight.get() - 1);
+                Poll::Ready(Ok(self.value))
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    struct Remote {
+        in_flight: Rc<Cell<usize>>,
+        peak: Rc<Cell<usize>>,
+        fail_above: Option<i32>,
+        nan_above: Option<i32>,
+    }
+
+    impl Remote {
+        fn new() -> Remote {
+            Remote {
+                in_flight: Rc::new(Cell::new(0)),
+                peak: Rc::new(Cell::new(0)),
+                fail_above: None,
+                nan_above: None,
+            }
+        }
+    }
+
+    impl AsyncContext for Remote {
+        type Solution = i32;
+
+        fn make_with_rng(&self, rng: &mut HiveRng) -> i32 {
+            rng.gen_range(0, 100)
+        }
+
+        fn explore_with_rng(&self, field: &[Candidate<i32>], n: usize, rng: &mut HiveRng) -> i32 {
+            field[n].solution + rng.gen_range(-10, 10)
+        }
+
+        fn evaluate(&self, solution: &i32) -> Evaluation {
+            if let Some(limit) = self.fail_above {
+                if *solution > limit {
+                    let error: Box<::std::error::Error + Send + Sync> = From::from("too far");
+                    return Box::pin(::std::future::ready(Err(error)));
+                }
+            }
+            if let Some(limit) = self.nan_above {
+                if *solution > limit {
+                    return Box::pin(::std::future::ready(Ok(f64::NAN)));
+                }
+            }
+            Box::pin(Delayed {
+                value: *solution as f64,
+                done: Arc::new(AtomicBool::new(false)),
+                started: false,
+                in_flight: self.in_flight.clone(),
+                peak: self.peak.clone(),
+            })
+        }
+    }
+
+    #[test]
+    fn evaluations_overlap() {
+        let builder = AsyncHiveBuilder::new(Remote::new(), 10).set_concurrency(50).set_seed(1);
+        let mut hive = block_on(builder.build()).unwrap();
+        let before = hive.get().fitness;
+        let best = block_on(hive.run_for_evaluations(200)).unwrap();
+        assert_eq!(hive.evaluations(), 10 + 200);
+        assert_eq!(hive.context().peak.get(), 50);
+        assert_eq!(hive.context().in_flight.get(), 0);
+        assert!(best.fitness > before);
+    }
+
+    #[test]
+    fn rounds_are_counted() {
+        let builder = AsyncHiveBuilder::new(Remote::new(), 5).set_retries(2).set_seed(3);
+        let mut hive = block_on(builder.build()).unwrap();
+        block_on(hive.run_for_rounds(8)).unwrap();
+        let statistics = hive.statistics();
+        assert_eq!(statistics.rounds, 8);
+        // The whole first round starts before any evaluation finishes. Later
+        // tasks skip candidates that are being scouted, and each variant
+        // sends out at most one scout.
+        assert!(statistics.evaluations >= 5 + 10);
+        assert!(statistics.evaluations <= 5 + 2 * 8 * 10);
+    }
+
+    #[test]
+    fn runs_wait_to_be_polled() {
+        let mut hive = block_on(AsyncHiveBuilder::new(Remote::new(), 5).build()).unwrap();
+        drop(hive.run_for_rounds(10));
+        assert_eq!(hive.evaluations(), 5);
+        assert_eq!(hive.statistics().rounds, 0);
+
+        block_on(hive.run_for_rounds(0)).unwrap();
+        assert_eq!(hive.evaluations(), 5);
+    }
+
+    /// Never improves on its field, and never finishes evaluating a scout.
+    struct Stalling {
+        workers: usize,
+        made: Cell<usize>,
+    }
+
+    impl AsyncContext for Stalling {
+        type Solution = i32;
+
+        fn make_with_rng(&self, _: &mut HiveRng) -> i32 {
+            let made = self.made.get();
+            self.made.set(made + 1);
+            if made < self.workers { 0 } else { -1 }
+        }
+
+        fn explore_with_rng(&self, field: &[Candidate<i32>], n: usize, _: &mut HiveRng) -> i32 {
+            field[n].solution
+        }
+
+        fn evaluate(&self, solution: &i32) -> Evaluation {
+            if *solution < 0 {
+                Box::pin(::std::future::pending())
+            } else {
+                Box::pin(::std::future::ready(Ok(*solution as f64)))
+            }
+        }
+    }
+
+    /// Wakes nothing, for polling a future by hand.
+    struct Idle;
+
+    impl Wake for Idle {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[test]
+    fn dropped_runs_forget_their_scouts() {
+        let context = Stalling {
+            workers: 3,
+            made: Cell::new(0),
+        };
+        let builder = AsyncHiveBuilder::new(context, 3).set_retries(1);
+        let mut hive = block_on(builder.build()).unwrap();
+        let waker = Waker::from(Arc::new(Idle));
+        let mut context = TaskContext::from_waker(&waker);
+
+        // Every candidate is exhausted at once, and its scout never returns.
+        let mut run = hive.run_for_rounds(10);
+        assert!(Pin::new(&mut run).poll(&mut context).is_pending());
+        assert_eq!(run.hive.scouting.len(), 3);
+        drop(run);
+        assert!(hive.scouting.is_empty());
+
+        let evaluations = hive.evaluations();
+        let mut run = hive.run_for_rounds(10);
+        assert!(Pin::new(&mut run).poll(&mut context).is_pending());
+        drop(run);
+        assert!(hive.evaluations() > evaluations);
+    }
+
+    #[test]
+    fn nan_fitness_is_an_error() {
+        let mut context = Remote::new();
+        context.nan_above = Some(120);
+        let mut hive = block_on(AsyncHiveBuilder::new(context, 5).build()).unwrap();
+        match block_on(hive.run_for_rounds(1000)) {
+            Err(AbcError::InvalidFitness(fitness)) => assert!(fitness.is_nan()),
+            other => panic!("Expected an invalid fitness, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn nan_fitness_as_worst() {
+        let mut context = Remote::new();
+        context.nan_above = Some(120);
+        let builder = AsyncHiveBuilder::new(context, 5).set_nan_as_worst(true);
+        let mut hive = block_on(builder.build()).unwrap();
+        let best = block_on(hive.run_for_rounds(100)).unwrap();
+        assert!(best.solution <= 120);
+        assert!(hive.field().iter().all(|candidate| candidate.fitness.is_finite()));
+    }
+
+    #[test]
+    fn failures_stop_the_run() {
+        let mut context = Remote::new();
+        context.fail_above = Some(120);
+        let mut hive = block_on(AsyncHiveBuilder::new(context, 5).build()).unwrap();
+        match block_on(hive.run_for_rounds(1000)) {
+            Err(AbcError::User(_)) => {}
+            other => panic!("Expected a user error, got {:?}", other.is_ok()),
+        }
+    }
+}
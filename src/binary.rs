@@ -0,0 +1,305 @@
+//! Ready-made context for optimizing over fixed-length bit strings.
+//!
+//! Many combinatorial problems, like feature selection or the knapsack
+//! problem, choose a subset of items, which is naturally written as a string
+//! of bits. A [`BinaryContext`](struct.BinaryContext.html) wraps a function
+//! of such a bit string, and searches the space with one of the established
+//! binary ABC operators, chosen with
+//! [`BinaryOperator`](enum.BinaryOperator.html).
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate abc; fn main() {
+//! use abc::HiveBuilder;
+//! use abc::binary::{BinaryContext, BinaryOperator};
+//!
+//! // 0/1 knapsack: maximize value without exceeding the capacity.
+//! let weights = [12.0, 2.0, 1.0, 1.0, 4.0];
+//! let values = [4.0, 2.0, 1.0, 2.0, 10.0];
+//! let context = BinaryContext::new(5, move |bits: &[bool]| {
+//!     let (weight, value) = bits.iter()
+//!                               .enumerate()
+//!                               .filter(|&(_, bit)| *bit)
+//!                               .fold((0.0, 0.0), |(w, v), (i, _)| (w + weights[i], v + values[i]));
+//!     if weight <= 15.0 { value } else { 0.0 }
+//! }).set_operator(BinaryOperator::AngleModulated);
+//!
+//! let hive = HiveBuilder::new(context, 10).build().unwrap();
+//! let best = hive.run_for_rounds(50).unwrap();
+//! println!("{:?}", best.solution.bits);
+//! # }
+//! ```
+
+extern crate rand;
+
+use std::f64::consts::PI;
+use std::hash::{Hash, Hasher};
+
+use self::rand::{thread_rng, Rng};
+
+use candidate::Candidate;
+use context::{Context, HiveRng, random_neighbour};
+use objective::Objective;
+
+/// Computes the objective value of a bit string.
+pub type BinaryFunction = Fn(&[bool]) -> f64 + Send + Sync + 'static;
+
+/// Neighbourhood operator used to explore near a bit string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOperator {
+    /// XOR-based, dissimilarity-driven move, in the style of DisABC.
+    ///
+    /// The bits in which the solution differs from a randomly chosen
+    /// neighbour are found by XOR. A factor φ is drawn uniformly from
+    /// [0, 1], and each of those bits is copied from the neighbour with
+    /// probability φ, so that the variant moves a random fraction of the way
+    /// towards the neighbour. If nothing changes, a single bit is flipped, so
+    /// that every variant differs from its source.
+    Xor,
+
+    /// Angle-modulated binary ABC.
+    ///
+    /// Each solution carries four real coefficients (*a*, *b*, *c*, *d*),
+    /// which are explored with the canonical continuous ABC update. The bits
+    /// are sampled from the generating function
+    ///
+    /// <center>*g*(*x*) = sin(2π(*x* − *a*) *b* cos(2π(*x* − *a*) *c*))
+    /// + *d*</center>
+    ///
+    /// at *x* = 0, 1, 2, …, with each bit set where *g*(*x*) > 0. This keeps
+    /// the search in four dimensions, however long the bit string.
+    AngleModulated,
+}
+
+/// A bit string, as generated by a [`BinaryContext`](struct.BinaryContext.html).
+///
+/// Bit strings compare and hash by their bits alone. To place a known bit
+/// string in a hive, convert it from its bits. Such a bit string has no
+/// generating coefficients, so under angle modulation it is explored with
+/// the XOR operator, until a scout replaces it.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BitString {
+    /// The bits themselves.
+    pub bits: Vec<bool>,
+
+    // Generating coefficients, when using angle modulation.
+    coefficients: Option<[f64; 4]>,
+}
+
+impl From<Vec<bool>> for BitString {
+    fn from(bits: Vec<bool>) -> BitString {
+        BitString {
+            bits: bits,
+            coefficients: None,
+        }
+    }
+}
+
+impl PartialEq for BitString {
+    fn eq(&self, other: &BitString) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl Eq for BitString {}
+
+impl Hash for BitString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits.hash(state)
+    }
+}
+
+/// Optimizes a function over fixed-length bit strings.
+///
+/// By default, the objective is maximized with the
+/// [`Xor`](enum.BinaryOperator.html#variant.Xor) operator.
+pub struct BinaryContext {
+    length: usize,
+    objective: Box<BinaryFunction>,
+    direction: Objective,
+    operator: BinaryOperator,
+}
+
+impl BinaryContext {
+    /// Creates a context that will maximize `objective`.
+    ///
+    /// * `length` - Number of bits in each solution.
+    /// * `objective` - Objective value of a bit string.
+    pub fn new<F>(length: usize, objective: F) -> BinaryContext
+        where F: Fn(&[bool]) -> f64 + Send + Sync + 'static
+    {
+        if length == 0 {
+            panic!("BinaryContext must have at least one bit.");
+        }
+
+        BinaryContext {
+            length: length,
+            objective: Box::new(objective),
+            direction: Objective::Maximize,
+            operator: BinaryOperator::Xor,
+        }
+    }
+
+    /// Sets whether the objective should be minimized or maximized.
+    ///
+    /// This defaults to `Objective::Maximize`.
+    pub fn set_direction(mut self, direction: Objective) -> BinaryContext {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the neighbourhood operator.
+    ///
+    /// This defaults to `BinaryOperator::Xor`.
+    pub fn set_operator(mut self, operator: BinaryOperator) -> BinaryContext {
+        self.operator = operator;
+        self
+    }
+
+    /// Returns the number of bits in each solution.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    fn modulate(&self, coefficients: [f64; 4]) -> BitString {
+        let [a, b, c, d] = coefficients;
+        let bits = (0..self.length)
+                       .map(|x| {
+                           let angle = 2.0 * PI * (x as f64 - a);
+                           (angle * b * (angle * c).cos()).sin() + d > 0.0
+                       })
+                       .collect();
+        BitString {
+            bits: bits,
+            coefficients: Some(coefficients),
+        }
+    }
+}
+
+impl Context for BinaryContext {
+    type Solution = BitString;
+
+    fn make(&self) -> BitString {
+        self.make_with_rng(&mut thread_rng().gen())
+    }
+
+    fn make_with_rng(&self, rng: &mut HiveRng) -> BitString {
+        match self.operator {
+            BinaryOperator::Xor => {
+                BitString {
+                    bits: (0..self.length).map(|_| rng.gen()).collect(),
+                    coefficients: None,
+                }
+            }
+            BinaryOperator::AngleModulated => {
+                let mut coefficients = [0.0; 4];
+                for coefficient in &mut coefficients {
+                    *coefficient = rng.gen_range(-1.0, 1.0);
+                }
+                self.modulate(coefficients)
+            }
+        }
+    }
+
+    fn evaluate_fitness(&self, solution: &BitString) -> f64 {
+        self.direction().fitness(self.evaluate_objective(solution))
+    }
+
+    fn evaluate_objective(&self, solution: &BitString) -> f64 {
+        (self.objective)(&solution.bits)
+    }
+
+    fn direction(&self) -> Objective {
+        self.direction
+    }
+
+    fn dimensions(&self) -> Option<usize> {
+        match self.operator {
+            BinaryOperator::Xor => Some(self.length),
+            BinaryOperator::AngleModulated => Some(4),
+        }
+    }
+
+    fn explore(&self, field: &[Candidate<BitString>], index: usize) -> BitString {
+        self.explore_with_rng(field, index, &mut thread_rng().gen())
+    }
+
+    fn explore_with_rng(&self,
+                        field: &[Candidate<BitString>],
+                        index: usize,
+                        rng: &mut HiveRng)
+                        -> BitString {
+        let current = &field[index].solution;
+        let other = &field[random_neighbour(field.len(), index, rng)].solution;
+
+        match (self.operator, current.coefficients, other.coefficients) {
+            (BinaryOperator::AngleModulated, Some(mut coefficients), Some(others)) => {
+                let j = rng.gen_range(0, 4);
+                let phi = rng.gen_range(-1.0, 1.0);
+                coefficients[j] += phi * (coefficients[j] - others[j]);
+                self.modulate(coefficients)
+            }
+            _ => {
+                let phi = rng.next_f64();
+                let mut variant = current.clone();
+                let mut changed = false;
+                for (bit, &theirs) in variant.bits.iter_mut().zip(&other.bits) {
+                    if (*bit ^ theirs) && rng.next_f64() < phi {
+                        *bit = theirs;
+                        changed = true;
+                    }
+                }
+                if !changed {
+                    let j = rng.gen_range(0, self.length);
+                    variant.bits[j] = !variant.bits[j];
+                }
+                variant.coefficients = None;
+                variant
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hive::HiveBuilder;
+
+    fn ones(bits: &[bool]) -> f64 {
+        bits.iter().filter(|bit| **bit).count() as f64
+    }
+
+    #[test]
+    fn xor_finds_all_ones() {
+        let hive = HiveBuilder::new(BinaryContext::new(16, ones), 10)
+                       .set_threads(1)
+                       .set_seed(3)
+                       .build()
+                       .unwrap();
+        let best = hive.run_for_rounds(200).unwrap();
+        assert_eq!(best.objective, 16.0);
+    }
+
+    #[test]
+    fn angle_modulation_keeps_length() {
+        let context = BinaryContext::new(40, ones).set_operator(BinaryOperator::AngleModulated);
+        let hive = HiveBuilder::new(context, 10).set_threads(1).set_seed(3).build().unwrap();
+        let best = hive.run_for_rounds(50).unwrap();
+        assert_eq!(best.solution.bits.len(), 40);
+        assert!(best.objective >= 20.0);
+    }
+
+    #[test]
+    fn known_bit_strings() {
+        let known = BitString::from(vec![true; 64]);
+        let hive = HiveBuilder::new(BinaryContext::new(64, ones), 10)
+                       .set_threads(1)
+                       .add_known_solution(known.clone())
+                       .build()
+                       .unwrap();
+        assert_eq!(hive.get().unwrap().solution, known);
+        assert_eq!(hive.get().unwrap().objective, 64.0);
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use result::Result as AbcResult;
+
+/// Remembers the objective values of solutions that have been evaluated.
+///
+/// This lets a [`HiveBuilder`](struct.HiveBuilder.html) hold a cache for
+/// any type of solution, although only hashable solutions can be cached.
+pub trait EvaluationCache<S>: Send + Sync {
+    /// Looks up a solution's objective values, counting a hit if it is
+    /// found.
+    fn get(&self, solution: &S) -> AbcResult<Option<Vec<f64>>>;
+
+    /// Counts `count` misses, once the solutions that were not found have
+    /// been passed on to the context.
+    fn count_misses(&self, count: usize);
+
+    /// Records a solution's objective values.
+    fn insert(&self, solution: &S, objectives: &[f64]) -> AbcResult<()>;
+
+    /// Returns the number of hits and misses so far.
+    fn counts(&self) -> (usize, usize);
+}
+
+/// A cache that forgets the least recently used solution once it is full.
+pub struct LruCache<S: Hash + Eq> {
+    capacity: usize,
+    state: Mutex<LruState<S>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+struct LruState<S: Hash + Eq> {
+    // Each solution's objective values, and the time it was last used.
+    entries: HashMap<S, (Vec<f64>, u64)>,
+    // The solution last used at each time, oldest first.
+    recency: BTreeMap<u64, S>,
+    clock: u64,
+}
+
+impl<S: Hash + Eq + Clone> LruCache<S> {
+    pub fn new(capacity: usize) -> LruCache<S> {
+        LruCache {
+            capacity: capacity,
+            state: Mutex::new(LruState {
+                entries: HashMap::with_capacity(capacity),
+                recency: BTreeMap::new(),
+                clock: 0,
+            }),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<S: Hash + Eq + Clone> LruState<S> {
+    /// Marks a cached solution as just used.
+    fn touch(&mut self, solution: &S) {
+        self.clock += 1;
+        let clock = self.clock;
+        if let Some(entry) = self.entries.get_mut(solution) {
+            self.recency.remove(&entry.1);
+            entry.1 = clock;
+            self.recency.insert(clock, solution.clone());
+        }
+    }
+}
+
+impl<S: Hash + Eq + Clone + Send + Sync> EvaluationCache<S> for LruCache<S> {
+    fn get(&self, solution: &S) -> AbcResult<Option<Vec<f64>>> {
+        let mut state = try!(self.state.lock());
+        let objectives = state.entries.get(solution).map(|entry| entry.0.clone());
+        if objectives.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            state.touch(solution);
+        }
+        Ok(objectives)
+    }
+
+    fn count_misses(&self, count: usize) {
+        self.misses.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn insert(&self, solution: &S, objectives: &[f64]) -> AbcResult<()> {
+        let mut state = try!(self.state.lock());
+        if state.entries.contains_key(solution) {
+            state.touch(solution);
+            return Ok(());
+        }
+
+        if state.entries.len() >= self.capacity {
+            let oldest = state.recency.keys().next().cloned();
+            if let Some(oldest) = oldest {
+                let forgotten = state.recency.remove(&oldest).unwrap();
+                state.entries.remove(&forgotten);
+            }
+        }
+        state.clock += 1;
+        let clock = state.clock;
+        state.entries.insert(solution.clone(), (objectives.to_vec(), clock));
+        state.recency.insert(clock, solution.clone());
+        Ok(())
+    }
+
+    fn counts(&self) -> (usize, usize) {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forgets_least_recently_used() {
+        let cache = LruCache::new(2);
+        cache.insert(&1, &[1.0]).unwrap();
+        cache.insert(&2, &[2.0]).unwrap();
+        assert_eq!(cache.get(&1).unwrap(), Some(vec![1.0]));
+
+        // 2 is now the least recently used.
+        cache.insert(&3, &[3.0]).unwrap();
+        assert_eq!(cache.get(&2).unwrap(), None);
+        assert_eq!(cache.get(&1).unwrap(), Some(vec![1.0]));
+        assert_eq!(cache.get(&3).unwrap(), Some(vec![3.0]));
+        assert_eq!(cache.counts(), (3, 0));
+
+        // Misses are only counted once they are evaluated.
+        cache.count_misses(1);
+        assert_eq!(cache.counts(), (3, 1));
+    }
+}
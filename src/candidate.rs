@@ -1,6 +1,8 @@
+use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// One solution being explored by the hive, plus additional data.
 ///
 /// This implementation was written with the expectation that the
@@ -13,16 +15,66 @@ pub struct Candidate<S: Clone + Send + Sync + 'static> {
 
     /// Cached fitness of the solution.
     pub fitness: f64,
+
+    /// Cached raw objective value of the solution.
+    ///
+    /// For contexts that only implement
+    /// [`evaluate_fitness`](trait.Context.html#method.evaluate_fitness), this
+    /// is the same as the fitness.
+    pub objective: f64,
+
+    /// Cached raw values of each of the solution's objectives.
+    ///
+    /// This is only filled in by hives that keep a
+    /// [Pareto archive](struct.HiveBuilder.html#method.set_pareto_archive),
+    /// in which case `objective` is the first of these values. Otherwise, it
+    /// is empty.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub objectives: Vec<f64>,
+
+    /// Cached constraint violation of the solution.
+    ///
+    /// This is zero for feasible solutions. See
+    /// [`Context::constraint_violation`](trait.Context.html#method.constraint_violation).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub violation: f64,
 }
 
 impl<S: Clone + Send + Sync + 'static> Candidate<S> {
     /// Wrap a solution with its cached fitness.
     pub fn new(solution: S, fitness: f64) -> Candidate<S> {
+        Candidate::with_objective(solution, fitness, fitness)
+    }
+
+    /// Wrap a solution with its cached raw objective value and fitness.
+    pub fn with_objective(solution: S, objective: f64, fitness: f64) -> Candidate<S> {
         Candidate {
             solution: solution,
             fitness: fitness,
+            objective: objective,
+            objectives: Vec::new(),
+            violation: 0.0,
         }
     }
+
+    /// Checks whether the solution satisfies all of its constraints.
+    pub fn is_feasible(&self) -> bool {
+        self.violation <= 0.0
+    }
+}
+
+/// Compares two candidates by Deb's feasibility rules.
+///
+/// A feasible candidate beats an infeasible one. Two feasible candidates are
+/// compared by fitness, and two infeasible candidates by violation, the
+/// smaller violation being better. The better candidate is `Greater`.
+pub fn compare<S: Clone + Send + Sync + 'static>(a: &Candidate<S>, b: &Candidate<S>) -> Ordering {
+    match (a.is_feasible(), b.is_feasible()) {
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.violation.partial_cmp(&a.violation).unwrap_or(Ordering::Equal),
+        (true, true) => a.fitness.partial_cmp(&b.fitness).unwrap_or(Ordering::Equal),
+    }
 }
 
 impl<S: Clone + Send + Sync + 'static> Debug for Candidate<S>
@@ -46,6 +98,17 @@ impl<S: Clone + Send + Sync + 'static> WorkingCandidate<S> {
         }
     }
 
+    pub fn with_retries(candidate: Candidate<S>, retries: i32) -> WorkingCandidate<S> {
+        WorkingCandidate {
+            candidate: candidate,
+            retries: retries,
+        }
+    }
+
+    pub fn retries(&self) -> i32 {
+        self.retries
+    }
+
     pub fn expired(&self) -> bool {
         self.retries <= 0
     }
@@ -54,3 +117,21 @@ impl<S: Clone + Send + Sync + 'static> WorkingCandidate<S> {
         self.retries -= 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(fitness: f64, violation: f64) -> Candidate<()> {
+        let mut candidate = Candidate::new((), fitness);
+        candidate.violation = violation;
+        candidate
+    }
+
+    #[test]
+    fn feasibility_rules() {
+        assert_eq!(compare(&candidate(1.0, 0.0), &candidate(5.0, 0.1)), Ordering::Greater);
+        assert_eq!(compare(&candidate(1.0, 0.2), &candidate(5.0, 0.1)), Ordering::Less);
+        assert_eq!(compare(&candidate(5.0, 0.0), &candidate(1.0, 0.0)), Ordering::Greater);
+    }
+}
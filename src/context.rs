@@ -1,4 +1,73 @@
+extern crate rand;
+
+use std::error::Error;
+
+use self::rand::{Rng, XorShiftRng};
+
 use candidate::Candidate;
+use objective::Objective;
+
+/// Random number generator handed to a `Context` by the hive.
+///
+/// Each of the hive's worker threads owns one of these. If the hive was built
+/// with [`set_seed`](struct.HiveBuilder.html#method.set_seed), every thread's
+/// generator is derived from that seed, so a context that draws all of its
+/// randomness from the generator it is given will behave reproducibly.
+pub type HiveRng = XorShiftRng;
+
+/// Result of one of a context's fallible methods.
+///
+/// Any error will do. The hive deals with it according to its
+/// [`FailurePolicy`](enum.FailurePolicy.html), and reports it as
+/// [`Error::User`](enum.Error.html#variant.User) if the run stops.
+pub type ContextResult<T> = Result<T, Box<Error + Send + Sync>>;
+
+/// Everything the hive knows that may help a context explore near a solution.
+///
+/// This is passed to [`Context::explore_in`](trait.Context.html#method.explore_in).
+pub struct Exploration<'a, S: Clone + Send + Sync + 'static> {
+    /// Snapshot of the hive's working candidates.
+    pub field: &'a [Candidate<S>],
+
+    /// Index in `field` of the candidate to be varied.
+    pub index: usize,
+
+    /// Best candidate found by the hive so far.
+    ///
+    /// Some variants of the algorithm, like the gbest-guided ABC of Zhu and
+    /// Kwong, pull new solutions towards the best one.
+    pub best: &'a Candidate<S>,
+
+    /// Probability with which each dimension of the solution should be
+    /// varied, as in the modified ABC of Akay and Karaboga.
+    ///
+    /// If this is `None`, the context should vary a single dimension, as in
+    /// the canonical algorithm. See
+    /// [`HiveBuilder::set_modification_rate`](struct.HiveBuilder.html#method.set_modification_rate).
+    pub modification_rate: Option<f64>,
+
+    /// Scale of the random factor φ, which should be drawn from
+    /// `[-phi_scale, phi_scale]`.
+    ///
+    /// This is 1 in the canonical algorithm. See
+    /// [`HiveBuilder::set_adaptive_phi`](struct.HiveBuilder.html#method.set_adaptive_phi).
+    pub phi_scale: f64,
+}
+
+/// Chooses the index of a candidate other than `index` at random.
+///
+/// If `index` is the only candidate in a field of `len`, returns `index`.
+pub fn random_neighbour(len: usize, index: usize, rng: &mut HiveRng) -> usize {
+    if len > 1 {
+        let mut other = rng.gen_range(0, len - 1);
+        if other >= index {
+            other += 1;
+        }
+        other
+    } else {
+        index
+    }
+}
 
 /// Context for generating and evaluating solutions.
 ///
@@ -17,6 +86,36 @@ use candidate::Candidate;
 /// locking mechanism. This will allow you to access the fields from multiple
 /// threads, without needing a `&mut` reference.
 ///
+/// Solutions are generated and varied by `make` and `explore`, or by their
+/// `make_with_rng` and `explore_with_rng` counterparts, which receive the
+/// calling thread's [`HiveRng`](type.HiveRng.html). The hive always calls the
+/// `_with_rng` forms, which default to the plain forms. Implement the
+/// `_with_rng` forms as well if you want runs with a fixed seed to be
+/// repeatable.
+/// Contexts that need more information from the hive while exploring, such
+/// as the best candidate so far, can implement `explore_in` as well.
+///
+/// Solutions are scored by `evaluate_fitness`, which the algorithm maximizes.
+/// A context may also implement `evaluate_objective` and `direction` to
+/// report a raw objective, such as a cost to be minimized. The hive then
+/// records the raw objective of each candidate, and derives the fitness from
+/// it; such a context's `evaluate_fitness` should agree, which
+/// [`Objective::fitness`](enum.Objective.html#method.fitness) makes easy.
+///
+/// For [multi-objective](struct.HiveBuilder.html#method.set_pareto_archive)
+/// runs, a context implements `try_evaluate_objectives` instead, returning
+/// a value for each objective.
+///
+/// Contexts whose work can fail, for example because they call out to an
+/// external simulator, can implement `try_make_with_rng`,
+/// `try_evaluate_objective` and `try_explore_in` as well. These return a
+/// [`ContextResult`](type.ContextResult.html), and are the methods that the
+/// hive actually calls; by default, they wrap the infallible methods in
+/// `Ok`. A context that implements `try_evaluate_objective` need not
+/// implement `evaluate_objective`. Failures, including panics in any of the
+/// context's methods, are handled according to the hive's
+/// [`FailurePolicy`](enum.FailurePolicy.html).
+///
 /// # Examples
 ///
 /// ```
@@ -61,6 +160,14 @@ pub trait Context : Send + Sync {
     /// Generates a fresh, random solution.
     fn make(&self) -> Self::Solution;
 
+    /// Generates a fresh, random solution, drawing randomness from `rng`.
+    ///
+    /// By default, this ignores `rng` and calls `make`.
+    fn make_with_rng(&self, rng: &mut HiveRng) -> Self::Solution {
+        let _ = rng;
+        self.make()
+    }
+
     /// Discovers the fitness of a solution (the algorithm will maximize this).
     ///
     /// Finding an optimal solution depends on having a way to determine
@@ -75,6 +182,86 @@ pub trait Context : Send + Sync {
     /// solution to be evaluated.
     fn evaluate_fitness(&self, solution: &Self::Solution) -> f64;
 
+    /// Discovers the raw objective value of a solution.
+    ///
+    /// Unlike the fitness, the objective need not be maximized: `direction`
+    /// says whether it should be minimized or maximized. The hive calls this
+    /// method, rather than `evaluate_fitness`, and stores both the objective
+    /// and the derived fitness in each [`Candidate`](struct.Candidate.html).
+    ///
+    /// By default, this calls `evaluate_fitness`, so the objective is the
+    /// fitness itself.
+    fn evaluate_objective(&self, solution: &Self::Solution) -> f64 {
+        self.evaluate_fitness(solution)
+    }
+
+    /// Number of dimensions in the search space, if it has a fixed number.
+    ///
+    /// The hive uses this to choose a default number of retries, following
+    /// Karaboga's recommendation of the number of workers times the number
+    /// of dimensions. The built-in contexts all report their dimensions.
+    ///
+    /// By default, this is `None`.
+    fn dimensions(&self) -> Option<usize> {
+        None
+    }
+
+    /// Finds the opposite of a solution, if the search space has opposites.
+    ///
+    /// This is used by [`Initializer::Opposition`](enum.Initializer.html).
+    /// For a value *x* in an interval [*a*, *b*], the opposite is
+    /// *a* + *b* − *x*.
+    ///
+    /// By default, this is `None`.
+    fn opposite(&self, solution: &Self::Solution) -> Option<Self::Solution> {
+        let _ = solution;
+        None
+    }
+
+    /// Maps a point in the unit hypercube to a solution, if the search space
+    /// can be mapped that way.
+    ///
+    /// The point has one coordinate in [0, 1) for each of the context's
+    /// [`dimensions`](#method.dimensions). This is used by the quasi-random
+    /// [`Initializer`](enum.Initializer.html) strategies.
+    ///
+    /// By default, this is `None`.
+    fn solution_at(&self, point: &[f64]) -> Option<Self::Solution> {
+        let _ = point;
+        None
+    }
+
+    /// Measures how far a solution is from satisfying the problem's
+    /// constraints.
+    ///
+    /// Feasible solutions have a violation of zero; for others, this is
+    /// typically the total amount by which the constraints are exceeded.
+    /// Rather than folding penalties into the fitness, the hive compares
+    /// candidates by Deb's feasibility rules: a feasible candidate beats an
+    /// infeasible one, two feasible candidates are compared by fitness, and
+    /// two infeasible candidates by violation, the smaller one winning. See
+    /// [`HiveBuilder::set_infeasible_acceptance`](struct.HiveBuilder.html#method.set_infeasible_acceptance)
+    /// to loosen these rules. Negative violations count as zero.
+    ///
+    /// While any candidate in the field is infeasible, observers choose
+    /// candidates as in Karaboga and Akay's constrained ABC: every feasible
+    /// candidate is weighted above every infeasible one, and infeasible
+    /// candidates are weighted by how little they violate the constraints.
+    ///
+    /// By default, every solution is feasible.
+    fn constraint_violation(&self, solution: &Self::Solution) -> f64 {
+        let _ = solution;
+        0.0
+    }
+
+    /// Direction in which `evaluate_objective` should be optimized.
+    ///
+    /// By default, this is `Objective::Maximize`, which uses the objective
+    /// value as the fitness.
+    fn direction(&self) -> Objective {
+        Objective::Maximize
+    }
+
     /// Looks "near" an existing solution.
     ///
     /// The user may wish to use information from the other solutions to build
@@ -83,4 +270,83 @@ pub trait Context : Send + Sync {
     /// that give information on the existing solutions, and the index of the
     /// solution to be modified.
     fn explore(&self, field: &[Candidate<Self::Solution>], index: usize) -> Self::Solution;
+
+    /// Looks "near" an existing solution, drawing randomness from `rng`.
+    ///
+    /// By default, this ignores `rng` and calls `explore`.
+    fn explore_with_rng(&self,
+                        field: &[Candidate<Self::Solution>],
+                        index: usize,
+                        rng: &mut HiveRng)
+                        -> Self::Solution {
+        let _ = rng;
+        self.explore(field, index)
+    }
+
+    /// Looks "near" an existing solution, with everything the hive knows.
+    ///
+    /// The hive always calls this method to explore. By default, it calls
+    /// `explore_with_rng` with the exploration's field and index.
+    fn explore_in(&self,
+                  exploration: &Exploration<Self::Solution>,
+                  rng: &mut HiveRng)
+                  -> Self::Solution {
+        self.explore_with_rng(exploration.field, exploration.index, rng)
+    }
+
+    /// Generates a new solution, or reports why it could not.
+    ///
+    /// By default, this calls `make_with_rng`.
+    fn try_make_with_rng(&self, rng: &mut HiveRng) -> ContextResult<Self::Solution> {
+        Ok(self.make_with_rng(rng))
+    }
+
+    /// Discovers the raw objective value of a solution, or reports why it
+    /// could not.
+    ///
+    /// By default, this calls `evaluate_objective`.
+    fn try_evaluate_objective(&self, solution: &Self::Solution) -> ContextResult<f64> {
+        Ok(self.evaluate_objective(solution))
+    }
+
+    /// Discovers the raw objective values of several solutions at once, or
+    /// reports why it could not.
+    ///
+    /// A [synchronous](enum.Mode.html#variant.Synchronous) hive evaluates all
+    /// of its workers' variants, then all of its observers' variants, with
+    /// one call to this method each, so contexts whose objective is cheaper
+    /// to compute in bulk should implement it. The result must hold one value
+    /// for each solution, in the same order. If this fails, every solution in
+    /// the batch is treated as having failed.
+    ///
+    /// By default, this calls `try_evaluate_objective` for each solution,
+    /// and fails with the first error.
+    fn try_evaluate_batch(&self, solutions: &[Self::Solution]) -> ContextResult<Vec<f64>> {
+        solutions.iter().map(|solution| self.try_evaluate_objective(solution)).collect()
+    }
+
+    /// Discovers the raw values of each of a solution's objectives, or
+    /// reports why it could not.
+    ///
+    /// This is only called by hives that keep a
+    /// [Pareto archive](struct.HiveBuilder.html#method.set_pareto_archive),
+    /// in place of `try_evaluate_objective`. Every objective is optimized in
+    /// the same `direction`, and every solution must have the same number of
+    /// objectives.
+    ///
+    /// By default, this returns the single value from
+    /// `try_evaluate_objective`.
+    fn try_evaluate_objectives(&self, solution: &Self::Solution) -> ContextResult<Vec<f64>> {
+        self.try_evaluate_objective(solution).map(|objective| vec![objective])
+    }
+
+    /// Looks "near" an existing solution, or reports why it could not.
+    ///
+    /// By default, this calls `explore_in`.
+    fn try_explore_in(&self,
+                      exploration: &Exploration<Self::Solution>,
+                      rng: &mut HiveRng)
+                      -> ContextResult<Self::Solution> {
+        Ok(self.explore_in(exploration, rng))
+    }
 }
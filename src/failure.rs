@@ -0,0 +1,40 @@
+/// What a hive does when one of its context's methods fails.
+///
+/// A method fails if one of the context's fallible methods, like
+/// [`try_evaluate_objective`](trait.Context.html#method.try_evaluate_objective),
+/// returns an error, or if any of the context's methods panics. Set the
+/// policy with
+/// [`HiveBuilder::set_failure_policy`](struct.HiveBuilder.html#method.set_failure_policy).
+///
+/// Whatever the policy, a failure to make a new solution that cannot be
+/// retried stops the run, since there is nothing to put in its place.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Stop the run, and return the error.
+    ///
+    /// This is the default.
+    Abort,
+
+    /// Call the failed method again, up to this many times, then stop the
+    /// run if it still fails.
+    ///
+    /// Each retry of an evaluation counts as an evaluation of its own. Once
+    /// the hive's evaluation budget is spent, failures are no longer
+    /// retried, and the run ends without them.
+    Retry(usize),
+
+    /// Treat a solution that could not be evaluated as having the worst
+    /// possible fitness, and an exploration that failed as one that found no
+    /// improvement.
+    ///
+    /// Either way, the candidate being worked on uses up one of its retries.
+    Worst,
+
+    /// Abandon the candidate being worked on at once, and replace it with a
+    /// new scout.
+    ///
+    /// If the scout itself cannot be evaluated, it is given the worst
+    /// possible fitness, and will be abandoned in turn once it runs out of
+    /// retries.
+    Rescout,
+}
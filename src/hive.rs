@@ -2,21 +2,37 @@ extern crate num_cpus;
 extern crate rand;
 extern crate crossbeam;
 
-use self::rand::{thread_rng, Rng};
+use self::rand::{thread_rng, Rng, SeedableRng};
 use self::crossbeam::{scope, ScopedJoinHandle};
 
-use std::ops::Range;
+use std::cmp::max;
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::sync::{Mutex, RwLock, MutexGuard};
+use std::sync::atomic::{AtomicUsize, AtomicU64, Ordering};
 use std::sync::mpsc::{Sender, Receiver, channel};
 use std::thread::spawn;
+use std::time::Instant;
 use std::collections::BTreeSet;
+use std::hash::Hash;
+use std::f64;
 
 use task::{TaskGenerator, Task};
-use candidate::{WorkingCandidate, Candidate};
-use context::Context;
+use candidate::{WorkingCandidate, Candidate, compare};
+use failure::FailurePolicy;
+use mode::Mode;
+use scout::ScoutPolicy;
+use initializer::{Initializer, latin_hypercube, halton};
+use pareto;
+use cache::{EvaluationCache, LruCache};
+use context::{Context, ContextResult, HiveRng, Exploration};
+use objective::Objective;
 use scaling::{ScalingFunction, proportionate};
-use result::{Result as AbcResult, Error as AbcError};
+use selection::{Selection, roulette};
+use stop::{StopCondition, Status};
+use statistics::Statistics;
+use observer::{HiveObserver, Bee};
+use snapshot::Snapshot;
+use result::{Result as AbcResult, Error as AbcError, catch_panic};
 
 /// Manages the parameters of the ABC algorithm.
 pub struct HiveBuilder<Ctx: Context> {
@@ -26,6 +42,22 @@ pub struct HiveBuilder<Ctx: Context> {
     context: Ctx,
     threads: usize,
     scale: Box<ScalingFunction>,
+    selection: Box<Selection>,
+    seed: Option<u64>,
+    nan_as_worst: bool,
+    failure: FailurePolicy,
+    mode: Mode,
+    scout_policy: Option<ScoutPolicy>,
+    initializer: Initializer,
+    known: Vec<Ctx::Solution>,
+    hive_observers: Vec<Box<HiveObserver<Ctx::Solution>>>,
+    snapshot: Option<Snapshot<Ctx::Solution>>,
+    modification_rate: Option<f64>,
+    phi_scale: f64,
+    phi_period: Option<usize>,
+    archive_capacity: Option<usize>,
+    infeasible_acceptance: f64,
+    cache: Option<Box<EvaluationCache<Ctx::Solution>>>,
 }
 
 impl<Ctx: Context> HiveBuilder<Ctx> {
@@ -38,14 +70,34 @@ impl<Ctx: Context> HiveBuilder<Ctx> {
             panic!("HiveBuilder must have at least one worker.");
         }
 
+        // Karaboga's limit is the number of food sources times the problem's
+        // dimension.
+        let retries = workers * context.dimensions().unwrap_or(1);
+
         HiveBuilder {
             workers: workers,
             observers: workers,
-            retries: workers,
+            retries: retries,
 
             context: context,
             threads: num_cpus::get(),
             scale: proportionate(),
+            selection: roulette(),
+            seed: None,
+            nan_as_worst: false,
+            failure: FailurePolicy::Abort,
+            mode: Mode::Asynchronous,
+            scout_policy: None,
+            initializer: Initializer::Random,
+            known: Vec::new(),
+            hive_observers: Vec::new(),
+            snapshot: None,
+            modification_rate: None,
+            phi_scale: 1.0,
+            phi_period: None,
+            archive_capacity: None,
+            infeasible_acceptance: 0.0,
+            cache: None,
         }
     }
 
@@ -59,7 +111,9 @@ impl<Ctx: Context> HiveBuilder<Ctx> {
 
     /// Sets the number of times a candidate can go unimproved before being reinitialized.
     ///
-    /// This defaults to the number of workers.
+    /// This defaults to the number of workers, multiplied by the context's
+    /// [`dimensions`](trait.Context.html#method.dimensions) if it has any,
+    /// which is the limit recommended by Karaboga.
     pub fn set_retries(mut self, retries: usize) -> HiveBuilder<Ctx> {
         self.retries = retries;
         self
@@ -77,16 +131,855 @@ impl<Ctx: Context> HiveBuilder<Ctx> {
         self
     }
 
+    /// Sets the strategy that observers use to choose candidates.
+    ///
+    /// The strategy works on the weights produced by the
+    /// [scaling function](#method.set_scaling). See the
+    /// [`selection`](selection/index.html) module for the available
+    /// strategies.
+    ///
+    /// This defaults to [roulette](selection/fn.roulette.html) selection.
+    pub fn set_selection(mut self, selection: Box<Selection>) -> HiveBuilder<Ctx> {
+        self.selection = selection;
+        self
+    }
+
+    /// Sets the probability with which each dimension of a solution is varied.
+    ///
+    /// In the canonical algorithm, exploring near a solution changes exactly
+    /// one of its dimensions. Akay and Karaboga's modified ABC instead changes
+    /// each dimension with probability `rate`, which speeds up convergence on
+    /// many problems. The rate is passed to the context in each
+    /// [`Exploration`](struct.Exploration.html). `RealVectorContext` and
+    /// `SpaceContext` honour it, and custom contexts may do so as they see
+    /// fit; `BinaryContext` and `PermutationContext` ignore it.
+    ///
+    /// By default, no rate is set, and a single dimension is varied.
+    pub fn set_modification_rate(mut self, rate: f64) -> HiveBuilder<Ctx> {
+        if !(rate > 0.0 && rate <= 1.0) {
+            panic!("HiveBuilder modification rate must be in (0, 1].");
+        }
+        self.modification_rate = Some(rate);
+        self
+    }
+
+    /// Sets the scale of the random factor φ used while exploring.
+    ///
+    /// The scale is passed to the context in each
+    /// [`Exploration`](struct.Exploration.html), and φ should be drawn from
+    /// `[-scale, scale]`. With [`set_adaptive_phi`](#method.set_adaptive_phi),
+    /// this is the starting scale.
+    ///
+    /// This defaults to 1, as in the canonical algorithm.
+    pub fn set_phi_scale(mut self, scale: f64) -> HiveBuilder<Ctx> {
+        self.phi_scale = scale;
+        self
+    }
+
+    /// Adapts the scale of φ with Rechenberg's 1/5 success rule.
+    ///
+    /// Every `period` rounds, the hive compares the proportion of explored
+    /// solutions that improved on their source with 1/5. If fewer succeeded,
+    /// the search is too coarse, and the scale is multiplied by 0.85; if
+    /// more succeeded, it is divided by 0.85. This is the adaptive scaling
+    /// factor of Akay and Karaboga's modified ABC.
+    ///
+    /// By default, the scale is fixed.
+    pub fn set_adaptive_phi(mut self, period: usize) -> HiveBuilder<Ctx> {
+        if period == 0 {
+            panic!("HiveBuilder adaptive phi period must be at least one round.");
+        }
+        self.phi_period = Some(period);
+        self
+    }
+
+    /// Sets whether NaN fitnesses are treated as the worst possible fitness.
+    ///
+    /// By default, a solution with a NaN fitness stops the hive with
+    /// [`Error::InvalidFitness`](enum.Error.html#variant.InvalidFitness). With
+    /// this set, it is given a fitness of negative infinity instead, so that
+    /// it never replaces another candidate, and is never chosen by observers
+    /// while a finite fitness is available. Its raw objective value is kept
+    /// as it was.
+    pub fn set_nan_as_worst(mut self, nan_as_worst: bool) -> HiveBuilder<Ctx> {
+        self.nan_as_worst = nan_as_worst;
+        self
+    }
+
+    /// Optimizes several objectives at once, keeping the best trade-offs
+    /// between them in an archive of at most `capacity` candidates.
+    ///
+    /// This turns the hive into a multi-objective ABC. Solutions are
+    /// evaluated with
+    /// [`Context::try_evaluate_objectives`](trait.Context.html#method.try_evaluate_objectives),
+    /// and a variant only replaces the candidate it was explored from if it
+    /// dominates it: that is, if it is at least as good in every objective,
+    /// and better in at least one. Observers choose candidates by their
+    /// Pareto rank in the field, so the non-dominated candidates are weighted
+    /// highest, and the [scaling function](#method.set_scaling) applies to
+    /// the rank-based fitnesses.
+    ///
+    /// Every feasible candidate that the hive evaluates is offered to the
+    /// archive, which keeps those that no other candidate dominates. Once it is full,
+    /// the most crowded candidates are dropped, to keep the archive spread
+    /// along the front. See [`Hive::archive`](struct.Hive.html#method.archive)
+    /// and [`Hive::stream`](struct.Hive.html#method.stream).
+    ///
+    /// The fitness of each candidate, and so the best candidate returned by
+    /// the `run` methods, still follows the first objective alone. Batches
+    /// of solutions are evaluated one at a time.
+    pub fn set_pareto_archive(mut self, capacity: usize) -> HiveBuilder<Ctx> {
+        if capacity == 0 {
+            panic!("The Pareto archive must have room for at least one candidate.");
+        }
+        self.archive_capacity = Some(capacity);
+        self
+    }
+
+    /// Sets the probability that a variant is compared with its candidate by
+    /// fitness alone, even though one of them is infeasible.
+    ///
+    /// By default, this is zero, so variants always replace candidates
+    /// according to Deb's feasibility rules (see
+    /// [`Context::constraint_violation`](trait.Context.html#method.constraint_violation)).
+    /// Accepting some infeasible moves, as in stochastic ranking, lets the
+    /// hive cross infeasible regions to reach other feasible ones. The best
+    /// candidate is always chosen by the feasibility rules.
+    pub fn set_infeasible_acceptance(mut self, probability: f64) -> HiveBuilder<Ctx> {
+        if !(probability >= 0.0 && probability.is_finite()) || probability > 1.0 {
+            panic!("The probability of accepting infeasible moves must be between 0 and 1.");
+        }
+        self.infeasible_acceptance = probability;
+        self
+    }
+
+    /// Sets how the hive schedules its bees' work.
+    ///
+    /// This defaults to `Mode::Asynchronous`.
+    pub fn set_mode(mut self, mode: Mode) -> HiveBuilder<Ctx> {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets when the hive abandons candidates that have run out of retries.
+    ///
+    /// This defaults to `ScoutPolicy::Immediate` in asynchronous mode, and
+    /// to `ScoutPolicy::OnePerCycle` in synchronous mode.
+    pub fn set_scout_policy(mut self, scout_policy: ScoutPolicy) -> HiveBuilder<Ctx> {
+        self.scout_policy = Some(scout_policy);
+        self
+    }
+
+    fn scout_policy(&self) -> ScoutPolicy {
+        match (self.scout_policy, self.mode) {
+            (Some(scout_policy), _) => scout_policy,
+            (None, Mode::Asynchronous) => ScoutPolicy::Immediate,
+            (None, Mode::Synchronous) => ScoutPolicy::OnePerCycle,
+        }
+    }
+
+    /// Sets how the hive fills its field with its first candidates.
+    ///
+    /// This defaults to `Initializer::Random`.
+    pub fn set_initializer(mut self, initializer: Initializer) -> HiveBuilder<Ctx> {
+        self.initializer = initializer;
+        self
+    }
+
+    /// Places a known solution in the hive's initial field.
+    ///
+    /// Known solutions are evaluated like any other, and always make it into
+    /// the field; the [initializer](#method.set_initializer) fills the
+    /// remaining places. There may be at most as many known solutions as
+    /// workers.
+    pub fn add_known_solution(mut self, solution: Ctx::Solution) -> HiveBuilder<Ctx> {
+        self.known.push(solution);
+        self
+    }
+
+    /// Sets what the hive does when one of the context's methods fails.
+    ///
+    /// This defaults to `FailurePolicy::Abort`, which stops the run.
+    pub fn set_failure_policy(mut self, failure: FailurePolicy) -> HiveBuilder<Ctx> {
+        self.failure = failure;
+        self
+    }
+
+    /// Seeds the random number generators used by the hive.
+    ///
+    /// Each worker thread gets its own generator, derived from `seed` and the
+    /// thread's position. These generators drive the observers' choices, and
+    /// are passed to [`Context::make_with_rng`](trait.Context.html#method.make_with_rng)
+    /// and [`Context::explore_with_rng`](trait.Context.html#method.explore_with_rng).
+    ///
+    /// If the hive runs on a single thread, and the context draws all of its
+    /// randomness from the generators it is given, then two hives built with
+    /// the same seed will produce identical sequences of candidates. With
+    /// several threads, the order in which the threads claim work still
+    /// varies from run to run.
+    ///
+    /// By default, the generators are seeded from `rand::thread_rng()`.
+    pub fn set_seed(mut self, seed: u64) -> HiveBuilder<Ctx> {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Registers an observer to be notified of events while the hive runs.
+    ///
+    /// Any number of observers may be added. They are notified in the order
+    /// in which they were added.
+    pub fn add_hive_observer(mut self,
+                             observer: Box<HiveObserver<Ctx::Solution>>)
+                             -> HiveBuilder<Ctx> {
+        self.hive_observers.push(observer);
+        self
+    }
+
+    /// Rebuilds the hive from a snapshot, rather than from new candidates.
+    ///
+    /// The number of workers is taken from the snapshot. All of the other
+    /// settings, including the number of observers, come from the builder as
+    /// usual. The snapshot is checked when the hive is built.
+    pub fn resume_from(mut self, snapshot: Snapshot<Ctx::Solution>) -> HiveBuilder<Ctx> {
+        self.workers = snapshot.candidates.len();
+        self.snapshot = Some(snapshot);
+        self
+    }
+
     /// Activates the `HiveBuilder` to create a runnable object.
+    ///
+    /// This fails with
+    /// [`Error::InvalidConfiguration`](enum.Error.html#variant.InvalidConfiguration)
+    /// if the hive has no threads to run on, if the scale of φ is not a
+    /// positive number, or if the hive is resumed from a snapshot that is
+    /// inconsistent.
     pub fn build(self) -> AbcResult<Hive<Ctx>> {
+        if self.threads == 0 {
+            return Err(AbcError::InvalidConfiguration("the hive has no threads".to_string()));
+        }
+        if !(self.phi_scale > 0.0 && self.phi_scale.is_finite()) {
+            return Err(AbcError::InvalidConfiguration(format!("the scale of phi is {}",
+                                                              self.phi_scale)));
+        }
+        if let Some(ref snapshot) = self.snapshot {
+            try!(check_snapshot(snapshot));
+        }
         Hive::new(self)
     }
 
-    fn new_candidate(&self) -> Candidate<Ctx::Solution> {
-        let solution = self.context.make();
-        let fitness = self.context.evaluate_fitness(&solution);
-        Candidate::new(solution, fitness)
+    /// Makes and evaluates a new solution.
+    ///
+    /// If the solution cannot be evaluated, but the failure policy tolerates
+    /// it, the solution is given the worst possible fitness.
+    fn new_candidate(&self,
+                     rng: &mut HiveRng,
+                     claim: &Claim)
+                     -> AbcResult<Option<Candidate<Ctx::Solution>>> {
+        let solution = try!(self.make(rng));
+        self.first_candidate(solution, claim)
+    }
+
+    /// Makes a new solution, retrying if the failure policy allows it.
+    fn make(&self, rng: &mut HiveRng) -> AbcResult<Ctx::Solution> {
+        self.attempt(|| catch_panic("make", || self.context.try_make_with_rng(rng)),
+                     || Ok(true))
+    }
+
+    /// Evaluates a solution with no candidate to compare against.
+    ///
+    /// If the solution cannot be evaluated, but the failure policy tolerates
+    /// it, the solution is given the worst possible fitness. Returns `None`
+    /// if the evaluation budget has been spent.
+    fn first_candidate(&self,
+                       solution: Ctx::Solution,
+                       claim: &Claim)
+                       -> AbcResult<Option<Candidate<Ctx::Solution>>> {
+        match self.objectives(&solution, claim) {
+            Ok(Some(objectives)) => self.candidate(solution, objectives).map(Some),
+            Ok(None) => Ok(None),
+            Err(error) => {
+                try!(self.tolerate(error, claim));
+                let mut candidate = Candidate::with_objective(solution, f64::NAN, f64::NEG_INFINITY);
+                candidate.violation = f64::INFINITY;
+                Ok(Some(candidate))
+            }
+        }
+    }
+
+    /// Explores near a solution, retrying if the failure policy allows it.
+    fn explore(&self,
+               exploration: &Exploration<Ctx::Solution>,
+               rng: &mut HiveRng)
+               -> AbcResult<Ctx::Solution> {
+        self.attempt(|| catch_panic("explore", || self.context.try_explore_in(exploration, rng)),
+                     || Ok(true))
+    }
+
+    /// Evaluates a solution, recording both its raw objective and its fitness.
+    ///
+    /// Returns `None` if the evaluation budget has been spent.
+    fn evaluate(&self,
+                solution: Ctx::Solution,
+                claim: &Claim)
+                -> AbcResult<Option<Candidate<Ctx::Solution>>> {
+        match try!(self.objectives(&solution, claim)) {
+            Some(objectives) => self.candidate(solution, objectives).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Evaluates several solutions, splitting them evenly between the hive's
+    /// threads.
+    ///
+    /// Solutions in the cache are not evaluated again. The others are
+    /// claimed from the evaluation budget in order, and if it runs out, the
+    /// solutions from there on are left out of the result. A solution that
+    /// cannot be evaluated, but whose failure the policy tolerates, is
+    /// returned as `None`.
+    fn evaluate_batch(&self,
+                      solutions: Vec<Ctx::Solution>,
+                      claim: &Claim)
+                      -> AbcResult<Vec<Option<Candidate<Ctx::Solution>>>> {
+        let mut cached = Vec::with_capacity(solutions.len());
+        let mut missing = Vec::new();
+        for solution in &solutions {
+            let objectives = try!(self.cached(solution));
+            if objectives.is_none() {
+                if !try!(claim(1)) {
+                    break;
+                }
+                missing.push(solution.clone());
+            }
+            cached.push(objectives);
+        }
+
+        self.count_misses(missing.len());
+        let mut fresh = try!(self.evaluate_shares(&missing, claim)).into_iter();
+        let mut candidates = Vec::with_capacity(cached.len());
+        for (solution, cached) in solutions.into_iter().zip(cached) {
+            let candidate = match cached.or_else(|| fresh.next().unwrap()) {
+                Some(objectives) => Some(try!(self.candidate(solution, objectives))),
+                None => None,
+            };
+            candidates.push(candidate);
+        }
+        Ok(candidates)
+    }
+
+    /// Finds the raw objectives of solutions whose evaluations have already
+    /// been claimed, giving each of the hive's threads an even share.
+    fn evaluate_shares(&self,
+                       solutions: &[Ctx::Solution],
+                       claim: &Claim)
+                       -> AbcResult<Vec<Option<Vec<f64>>>> {
+        let size = solutions.len().div_ceil(self.threads);
+        if size == solutions.len() {
+            return self.evaluate_share(solutions, claim);
+        }
+
+        let mut handles: Vec<ScopedJoinHandle<AbcResult<Vec<_>>>> = Vec::new();
+        scope(|scope| {
+            for share in solutions.chunks(size) {
+                handles.push(scope.spawn(move || self.evaluate_share(share, claim)));
+            }
+
+            let mut objectives = Vec::with_capacity(solutions.len());
+            for handle in handles.drain(..) {
+                objectives.extend(try!(handle.join()));
+            }
+            Ok(objectives)
+        })
+    }
+
+    /// Finds the raw objectives of one thread's share of a batch, and
+    /// remembers them.
+    ///
+    /// A hive with a Pareto archive evaluates each solution's objectives in
+    /// turn; otherwise the share is evaluated with one call to
+    /// [`Context::try_evaluate_batch`](trait.Context.html#method.try_evaluate_batch).
+    fn evaluate_share(&self,
+                      share: &[Ctx::Solution],
+                      claim: &Claim)
+                      -> AbcResult<Vec<Option<Vec<f64>>>> {
+        if self.archive_capacity.is_some() {
+            let mut objectives = Vec::with_capacity(share.len());
+            for solution in share {
+                match self.evaluate_objectives(solution, claim) {
+                    Ok(values) => {
+                        try!(self.remember(solution, &values));
+                        objectives.push(Some(values));
+                    }
+                    Err(error) => {
+                        try!(self.tolerate(error, claim));
+                        objectives.push(None);
+                    }
+                }
+            }
+            return Ok(objectives);
+        }
+
+        if share.is_empty() {
+            return Ok(Vec::new());
+        }
+        let objectives = self.attempt(|| {
+                                          catch_panic("evaluate",
+                                                      || self.context.try_evaluate_batch(share))
+                                      },
+                                      || claim(share.len()));
+        match objectives {
+            Ok(ref objectives) if objectives.len() != share.len() => {
+                let message = format!("try_evaluate_batch returned {} values for {} solutions",
+                                      objectives.len(),
+                                      share.len());
+                Err(AbcError::User(From::from(message)))
+            }
+            Ok(objectives) => {
+                for (solution, objective) in share.iter().zip(&objectives) {
+                    try!(self.remember(solution, &[*objective]));
+                }
+                Ok(objectives.into_iter().map(|objective| Some(vec![objective])).collect())
+            }
+            Err(error) => {
+                try!(self.tolerate(error, claim));
+                Ok(vec![None; share.len()])
+            }
+        }
+    }
+
+    /// Finds a solution's raw objective, retrying if the failure policy
+    /// allows it.
+    fn objective(&self, solution: &Ctx::Solution, claim: &Claim) -> AbcResult<f64> {
+        self.attempt(|| catch_panic("evaluate", || self.context.try_evaluate_objective(solution)),
+                     || claim(1))
+    }
+
+    /// Finds a solution's raw objectives, from the cache if possible.
+    ///
+    /// Only solutions that miss the cache are claimed from the evaluation
+    /// budget; returns `None` if it has been spent.
+    fn objectives(&self, solution: &Ctx::Solution, claim: &Claim) -> AbcResult<Option<Vec<f64>>> {
+        if let Some(objectives) = try!(self.cached(solution)) {
+            return Ok(Some(objectives));
+        }
+        if !try!(claim(1)) {
+            return Ok(None);
+        }
+        self.count_misses(1);
+        let objectives = try!(self.evaluate_objectives(solution, claim));
+        try!(self.remember(solution, &objectives));
+        Ok(Some(objectives))
+    }
+
+    /// Looks up a solution's raw objectives in the cache, if there is one.
+    fn cached(&self, solution: &Ctx::Solution) -> AbcResult<Option<Vec<f64>>> {
+        match self.cache {
+            Some(ref cache) => cache.get(solution),
+            None => Ok(None),
+        }
+    }
+
+    /// Counts solutions that missed the cache, if there is one, once their
+    /// evaluations have been claimed.
+    fn count_misses(&self, count: usize) {
+        if let Some(ref cache) = self.cache {
+            cache.count_misses(count);
+        }
+    }
+
+    /// Records a solution's raw objectives in the cache, if there is one.
+    fn remember(&self, solution: &Ctx::Solution, objectives: &[f64]) -> AbcResult<()> {
+        match self.cache {
+            Some(ref cache) => cache.insert(solution, objectives),
+            None => Ok(()),
+        }
+    }
+
+    /// Finds a solution's raw objectives, retrying if the failure policy
+    /// allows it.
+    ///
+    /// Unless the hive keeps a Pareto archive, this is the single objective
+    /// from [`objective`](#method.objective).
+    fn evaluate_objectives(&self, solution: &Ctx::Solution, claim: &Claim) -> AbcResult<Vec<f64>> {
+        if self.archive_capacity.is_none() {
+            return self.objective(solution, claim).map(|objective| vec![objective]);
+        }
+        let objectives = try!(self.attempt(|| {
+                                               catch_panic("evaluate", || {
+                                                   self.context.try_evaluate_objectives(solution)
+                                               })
+                                           },
+                                           || claim(1)));
+        if objectives.is_empty() {
+            let message = "try_evaluate_objectives returned no values".to_string();
+            return Err(AbcError::User(From::from(message)));
+        }
+        Ok(objectives)
+    }
+
+    /// Derives a candidate's fitness from its raw objectives, the first of
+    /// which is the candidate's objective, and measures its constraint
+    /// violation.
+    fn candidate(&self,
+                 solution: Ctx::Solution,
+                 objectives: Vec<f64>)
+                 -> AbcResult<Candidate<Ctx::Solution>> {
+        let mut candidate = try!(assess(solution,
+                                        &objectives,
+                                        self.context.direction(),
+                                        self.nan_as_worst,
+                                        |solution| self.context.constraint_violation(solution)));
+        if self.archive_capacity.is_some() {
+            candidate.objectives = objectives;
+        }
+        Ok(candidate)
+    }
+
+    /// Checks whether a variant should replace the candidate it was explored
+    /// from.
+    ///
+    /// If either of them is infeasible, they are compared by the feasibility
+    /// rules, unless the hive accepts this infeasible move. With a Pareto
+    /// archive, the variant must otherwise dominate the candidate, unless
+    /// either of them could not be evaluated.
+    fn improves(&self,
+                variant: &Candidate<Ctx::Solution>,
+                candidate: &Candidate<Ctx::Solution>,
+                rng: &mut HiveRng)
+                -> bool {
+        let feasible = variant.is_feasible() && candidate.is_feasible();
+        if !feasible && rng.next_f64() >= self.infeasible_acceptance {
+            compare(variant, candidate) == ::std::cmp::Ordering::Greater
+        } else if self.archive_capacity.is_some() && pareto::comparable(&variant.objectives) &&
+           pareto::comparable(&candidate.objectives) {
+            pareto::dominates(&variant.objectives,
+                              &candidate.objectives,
+                              self.context.direction())
+        } else {
+            variant.fitness > candidate.fitness
+        }
+    }
+
+    /// Calls one of the context's fallible methods, as many times as the
+    /// failure policy allows.
+    ///
+    /// Before each retry, `retry` is called, and the method is only retried
+    /// if it returns `true`. Evaluations use this to claim each retry from
+    /// the hive's budget.
+    fn attempt<T, F, R>(&self, mut method: F, mut retry: R) -> AbcResult<T>
+        where F: FnMut() -> AbcResult<ContextResult<T>>,
+              R: FnMut() -> AbcResult<bool>
+    {
+        let retries = match self.failure {
+            FailurePolicy::Retry(retries) => retries,
+            _ => 0,
+        };
+
+        let mut result = try!(method());
+        for _ in 0..retries {
+            if result.is_ok() || !try!(retry()) {
+                break;
+            }
+            result = try!(method());
+        }
+        result.map_err(AbcError::User)
+    }
+
+    /// Decides whether the hive can carry on after an error.
+    ///
+    /// Once the evaluation budget is spent, failures can no longer be
+    /// retried, so rather than stop the run over one, it is tolerated; the
+    /// run is ending anyway.
+    fn tolerate(&self, error: AbcError, claim: &Claim) -> AbcResult<()> {
+        let tolerant = match self.failure {
+            FailurePolicy::Abort => false,
+            FailurePolicy::Retry(_) => !try!(claim(0)),
+            FailurePolicy::Worst | FailurePolicy::Rescout => true,
+        };
+        match error {
+            AbcError::Panicked { .. } | AbcError::User(_) if tolerant => Ok(()),
+            _ => Err(error),
+        }
+    }
+
+    /// Plans the candidates of the initial field, according to the
+    /// initializer, after any known solutions.
+    fn initial_jobs(&mut self, rng: &mut HiveRng) -> AbcResult<Vec<Job<Ctx::Solution>>> {
+        if self.known.len() > self.workers {
+            let reason = format!("{} known solutions for {} workers",
+                                 self.known.len(),
+                                 self.workers);
+            return Err(AbcError::InvalidConfiguration(reason));
+        }
+        let count = self.workers - self.known.len();
+        let mut jobs = self.known.drain(..).map(Job::Known).collect::<Vec<_>>();
+
+        let unsupported = || {
+            AbcError::InvalidConfiguration("the context does not support the initializer"
+                                               .to_string())
+        };
+        match self.initializer {
+            Initializer::Random => jobs.extend((0..count).map(|_| Job::Make)),
+            Initializer::Opposition => {
+                for _ in 0..count {
+                    let solution = try!(self.make(rng));
+                    let opposite = try!(self.context.opposite(&solution).ok_or_else(&unsupported));
+                    jobs.push(Job::Generated(solution));
+                    jobs.push(Job::Generated(opposite));
+                }
+            }
+            Initializer::LatinHypercube | Initializer::Halton => {
+                let dimensions = try!(self.context.dimensions().ok_or_else(&unsupported));
+                let points = if self.initializer == Initializer::Halton {
+                    halton(count, dimensions, rng)
+                } else {
+                    latin_hypercube(count, dimensions, rng)
+                };
+                for point in points {
+                    let solution = try!(self.context.solution_at(&point).ok_or_else(&unsupported));
+                    jobs.push(Job::Generated(solution));
+                }
+            }
+        }
+        Ok(jobs)
+    }
+
+    /// Creates one generator for each worker thread.
+    fn new_rngs(&self) -> Vec<Mutex<HiveRng>> {
+        (0..self.threads)
+            .map(|thread| {
+                let rng = match self.seed {
+                    Some(seed) => derive_rng(seed, thread as u64),
+                    None => thread_rng().gen(),
+                };
+                Mutex::new(rng)
+            })
+            .collect()
+    }
+}
+
+impl<Ctx: Context> HiveBuilder<Ctx>
+    where Ctx::Solution: Hash + Eq
+{
+    /// Remembers the raw objectives of up to `capacity` solutions, so that
+    /// solutions that come up again are not evaluated again.
+    ///
+    /// This pays off in discrete search spaces, where workers and scouts
+    /// often propose solutions that have already been evaluated. Once the
+    /// cache is full, the least recently used solution is forgotten. The
+    /// cache is shared by all of the hive's threads, and only holds
+    /// solutions that were evaluated successfully.
+    ///
+    /// Only the solutions that miss the cache count towards the hive's
+    /// evaluations, and so towards the budget of
+    /// [`Hive::run_for_evaluations`](struct.Hive.html#method.run_for_evaluations).
+    /// A hive that keeps revisiting cached solutions may therefore take many
+    /// rounds to spend its budget, or never spend it at all in a small enough
+    /// search space; bound such runs with
+    /// [`Hive::run_until`](struct.Hive.html#method.run_until) or by rounds.
+    /// The number of cache hits and misses is reported in the hive's
+    /// [`Statistics`](struct.Statistics.html). A resumed hive starts with an
+    /// empty cache.
+    pub fn set_evaluation_cache(mut self, capacity: usize) -> HiveBuilder<Ctx> {
+        if capacity == 0 {
+            panic!("The evaluation cache must have room for at least one solution.");
+        }
+        self.cache = Some(Box::new(LruCache::new(capacity)));
+        self
+    }
+}
+
+/// Checks the weights produced by a scaling function, and makes them usable.
+///
+/// Weights that are NaN or positive infinity cannot be compared, and are
+/// rejected. Negative infinity marks the worst possible candidate, and gets
+/// no weight at all. If any of the remaining weights are negative, all of
+/// them are shifted up, so that the least of them becomes zero.
+pub(crate) fn validate_weights(mut weights: Vec<f64>) -> AbcResult<Vec<f64>> {
+    let mut floor = 0_f64;
+    for weight in &weights {
+        if weight.is_nan() || *weight == f64::INFINITY {
+            return Err(AbcError::InvalidFitness(*weight));
+        }
+        if weight.is_finite() {
+            floor = floor.min(*weight);
+        }
+    }
+
+    for weight in &mut weights {
+        *weight = if weight.is_finite() {
+            *weight - floor
+        } else {
+            0_f64
+        };
+    }
+    Ok(weights)
+}
+
+/// Derives a candidate's fitness from its raw objectives, the first of which
+/// is the candidate's objective, and measures its constraint violation.
+///
+/// A NaN objective or violation is an error, unless `nan_as_worst` is set,
+/// in which case the candidate is made as unfit as possible.
+pub(crate) fn assess<S, F>(solution: S,
+                           objectives: &[f64],
+                           direction: Objective,
+                           nan_as_worst: bool,
+                           constraint_violation: F)
+                          -> AbcResult<Candidate<S>>
+    where S: Clone + Send + Sync + 'static,
+          F: FnOnce(&S) -> f64
+{
+    let objective = objectives[0];
+    let mut fitness = direction.fitness(objective);
+    if fitness.is_nan() || objectives.iter().any(|value| value.is_nan()) {
+        if !nan_as_worst {
+            return Err(AbcError::InvalidFitness(f64::NAN));
+        }
+        fitness = f64::NEG_INFINITY;
+    }
+
+    let mut violation = try!(catch_panic("constraint_violation", || {
+        constraint_violation(&solution)
+    }));
+    if violation.is_nan() {
+        if !nan_as_worst {
+            return Err(AbcError::InvalidFitness(violation));
+        }
+        violation = f64::INFINITY;
+    }
+
+    let mut candidate = Candidate::with_objective(solution, objective, fitness);
+    candidate.violation = violation.max(0.0);
+    Ok(candidate)
+}
+
+/// Chooses a candidate for an observer.
+///
+/// Choices already made are handed out first, skipping any candidates that
+/// have started scouting since. Otherwise, the candidates that are not being
+/// scouted are weighed by `weights`, then by their feasibility, and
+/// `selection` makes as many choices at once as it needs: one for each of
+/// the `observers`, or just the one. If every candidate is being scouted,
+/// one is picked at random.
+pub(crate) fn choose<S, W>(selections: &mut Vec<usize>,
+                           field: &[Candidate<S>],
+                           weights: W,
+                           scouting: &BTreeSet<usize>,
+                           selection: &Selection,
+                           observers: usize,
+                           rng: &mut HiveRng)
+                          -> AbcResult<usize>
+    where S: Clone + Send + Sync + 'static,
+          W: FnOnce() -> AbcResult<Vec<f64>>
+{
+    while let Some(i) = selections.pop() {
+        if !scouting.contains(&i) {
+            return Ok(i);
+        }
+    }
+
+    let weights = feasibility_weights(field, try!(weights()));
+
+    // Avoid observing candidates that are being scouted.
+    let (indices, weights): (Vec<usize>, Vec<f64>) = weights.into_iter()
+                                                            .enumerate()
+                                                            .filter(|&(i, _)| {
+                                                                !scouting.contains(&i)
+                                                            })
+                                                            .unzip();
+
+    // If we are currently scouting all of the solutions, pick one at random.
+    if indices.is_empty() {
+        return Ok(rng.gen_range(0, field.len()));
+    }
+
+    let count = if selection.whole_round() {
+        max(observers, 1)
+    } else {
+        1
+    };
+    *selections = selection.select(&weights, count, rng)
+                           .into_iter()
+                           .map(|position| indices[position])
+                           .collect();
+    Ok(selections.pop().unwrap_or(indices[0]))
+}
+
+/// Weighs the candidates for the observers, as in Karaboga and Akay's
+/// constrained ABC, if any of them is infeasible.
+///
+/// A feasible candidate gets 0.5 plus half of its share of the feasible
+/// candidates' `weights`, and an infeasible one gets half of one less its
+/// share of the infeasible candidates' violations. If every candidate is
+/// feasible, the weights are left as they are.
+fn feasibility_weights<S>(field: &[Candidate<S>], weights: Vec<f64>) -> Vec<f64>
+    where S: Clone + Send + Sync + 'static
+{
+    if field.iter().all(Candidate::is_feasible) {
+        return weights;
+    }
+    let (mut total_weight, mut total_violation) = (0_f64, 0_f64);
+    for (candidate, weight) in field.iter().zip(&weights) {
+        if candidate.is_feasible() {
+            total_weight += *weight;
+        } else {
+            total_violation += candidate.violation;
+        }
+    }
+
+    field.iter()
+         .zip(weights)
+         .map(|(candidate, weight)| {
+             if !candidate.is_feasible() {
+                 0.5 * (1.0 - candidate.violation / total_violation)
+             } else if total_weight > 0.0 {
+                 0.5 + 0.5 * weight / total_weight
+             } else {
+                 0.5
+             }
+         })
+         .collect()
+}
+
+/// Checks that a hive can be resumed from a snapshot.
+///
+/// Snapshots are typically read back from disk, so a damaged one is reported
+/// as an invalid configuration, rather than trusted.
+fn check_snapshot<S: Clone + Send + Sync + 'static>(snapshot: &Snapshot<S>) -> AbcResult<()> {
+    let reason = if snapshot.candidates.is_empty() {
+        "the snapshot has no candidates".to_string()
+    } else if snapshot.candidates.len() != snapshot.retries.len() {
+        format!("the snapshot has {} retry counts for {} candidates",
+                snapshot.retries.len(),
+                snapshot.candidates.len())
+    } else if !(snapshot.phi_scale > 0.0 && snapshot.phi_scale.is_finite()) {
+        format!("the snapshot's scale of phi is {}", snapshot.phi_scale)
+    } else {
+        return Ok(());
+    };
+    Err(AbcError::InvalidConfiguration(reason))
+}
+
+/// Derives a generator from a seed and a stream number.
+///
+/// The seed and stream are mixed with SplitMix64, so that nearby seeds (and
+/// neighbouring threads) still get unrelated generators.
+pub(crate) fn derive_rng(seed: u64, stream: u64) -> HiveRng {
+    let mut state = seed ^ stream.wrapping_mul(0xd1b5_4a32_d192_ed03);
+    let mut next = || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    let (a, b) = (next(), next());
+    let mut words = [a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32];
+    // XorShift cannot be seeded with all zeroes.
+    if words.iter().all(|&w| w == 0) {
+        words[0] = 1;
     }
+    HiveRng::from_seed(words)
 }
 
 /// Runs the ABC algorithm, maintaining any necessary state.
@@ -96,31 +989,138 @@ pub struct Hive<Ctx: Context> {
     working: Vec<RwLock<WorkingCandidate<Ctx::Solution>>>,
     best: Mutex<Candidate<Ctx::Solution>>,
     scouting: RwLock<BTreeSet<usize>>,
+    rngs: Vec<Mutex<HiveRng>>,
+    evaluations: AtomicUsize,
+    budget: AtomicUsize,
+    rounds: AtomicUsize,
+
+    // Adaptive scale of phi, stored as the bits of an `f64`, with the number
+    // of explorations and improvements since it was last adapted.
+    phi_scale: AtomicU64,
+    explored: AtomicUsize,
+    improved: AtomicUsize,
+
+    // Observers' choices that have yet to be handed out.
+    selections: Mutex<Vec<usize>>,
+
+    // Candidates waiting to be abandoned at the end of the round.
+    exhausted: Mutex<BTreeSet<usize>>,
+
+    // Non-dominated candidates, if the hive keeps a Pareto archive.
+    archive: Mutex<Vec<Candidate<Ctx::Solution>>>,
 
     tasks: Mutex<Option<TaskGenerator>>,
+    condition: Mutex<Option<Stopping>>,
     sender: Option<Mutex<Sender<Candidate<Ctx::Solution>>>>,
 }
 
+/// Claims a number of fitness evaluations from a hive's budget, returning
+/// `false` if there is not enough left.
+///
+/// Claiming none checks whether the budget has been spent.
+type Claim<'a> = Fn(usize) -> AbcResult<bool> + Sync + 'a;
+
+/// A candidate to be added to the initial field.
+enum Job<S> {
+    /// Make a new solution.
+    Make,
+
+    /// Use a solution supplied by the user, which is always kept.
+    Known(S),
+
+    /// Use a solution planned by the initializer, which may be culled.
+    Generated(S),
+}
+
+/// A stop condition, plus the information needed to report on a run.
+struct Stopping {
+    condition: Box<StopCondition>,
+    started: Instant,
+    evaluations: usize,
+}
+
 impl<Ctx: Context> Hive<Ctx> {
-    fn new(hive: HiveBuilder<Ctx>) -> AbcResult<Hive<Ctx>> {
+    fn new(mut hive: HiveBuilder<Ctx>) -> AbcResult<Hive<Ctx>> {
+        let rngs = hive.new_rngs();
+
+        if let Some(snapshot) = hive.snapshot.take() {
+            hive.phi_scale = snapshot.phi_scale;
+            let working = snapshot.candidates
+                                  .into_iter()
+                                  .zip(snapshot.retries)
+                                  .map(|(c, retries)| {
+                                      RwLock::new(WorkingCandidate::with_retries(c, retries))
+                                  })
+                                  .collect();
+            let hive = Hive::assemble(hive,
+                                      rngs,
+                                      working,
+                                      snapshot.best,
+                                      snapshot.evaluations,
+                                      snapshot.rounds);
+            for member in &snapshot.archive {
+                try!(hive.update_archive(member));
+            }
+            return hive.archive_field();
+        }
+
         // Start by populating the field with an initial set of solution candidates.
 
         // Feed the worker threads a total of N items, each signifying that
         // we need another candidate.
-        let tokens: Mutex<Range<usize>> = Mutex::new(0..hive.workers);
+        let mut jobs = {
+            let mut rng = try!(rngs[0].lock());
+            try!(hive.initial_jobs(&mut rng))
+        };
+        jobs.reverse();
+        let tokens = Mutex::new(jobs);
+
+        // Count the evaluations that the candidates needed, including any
+        // retries, but not those found in the cache.
+        let claimed = AtomicUsize::new(0);
+        let claim = |count| {
+            claimed.fetch_add(count, Ordering::Relaxed);
+            Ok(true)
+        };
 
+        let known = Mutex::new(Vec::new());
         let candidates = Mutex::new(Vec::with_capacity(hive.workers));
         let mut handles = Vec::<ScopedJoinHandle<AbcResult<()>>>::with_capacity(hive.threads);
 
+        // A synchronous hive makes its initial field on one thread, like the
+        // rest of its run, so that seeded runs repeat whatever the threads.
+        let initial_rngs = match hive.mode {
+            Mode::Asynchronous => &rngs[..],
+            Mode::Synchronous => &rngs[..1],
+        };
+
         try!(crossbeam::scope(|scope| {
-            for _ in 0..hive.threads {
-                handles.push(scope.spawn(|| {
-                    while let Some(_) = {
+            for rng_mutex in initial_rngs {
+                let hive = &hive;
+                let tokens = &tokens;
+                let known = &known;
+                let candidates = &candidates;
+                let claim = &claim;
+                handles.push(scope.spawn(move || {
+                    let mut rng = try!(rng_mutex.lock());
+                    while let Some(job) = {
                         let mut guard = tokens.lock().unwrap();
-                        guard.next()
+                        guard.pop()
                     } {
-                        let candidate = hive.new_candidate();
-                        try!(candidates.lock()).push(candidate);
+                        match job {
+                            Job::Make => {
+                                let candidate = try!(hive.new_candidate(&mut rng, claim));
+                                try!(candidates.lock()).extend(candidate);
+                            }
+                            Job::Known(solution) => {
+                                let candidate = try!(hive.first_candidate(solution, claim));
+                                try!(known.lock()).extend(candidate);
+                            }
+                            Job::Generated(solution) => {
+                                let candidate = try!(hive.first_candidate(solution, claim));
+                                try!(candidates.lock()).extend(candidate);
+                            }
+                        }
                     }
                     Ok(())
                 }));
@@ -135,6 +1135,16 @@ impl<Ctx: Context> Hive<Ctx> {
         // We don't need the mutex anymore, since we're no longer populating
         // the candidate set from multiple threads.
         let mut candidates = try!(candidates.into_inner());
+        let evaluations = claimed.load(Ordering::Relaxed);
+
+        // Keep the fittest of the initializer's candidates, in case it made
+        // more than there is room for, then add the known ones.
+        let room = hive.workers - try!(known.lock()).len();
+        if candidates.len() > room {
+            candidates.sort_by(|a, b| compare(b, a));
+            candidates.truncate(room);
+        }
+        candidates.extend(try!(known.into_inner()));
 
         // Find the current best candidate, since we want to cache the best
         // at any given moment.
@@ -142,13 +1152,13 @@ impl<Ctx: Context> Hive<Ctx> {
             let (first, rest) = candidates.split_first().unwrap();
             let best_candidate = rest.iter()
                                      .fold(first, |best, next| {
-                                         if next.fitness > best.fitness {
+                                         if compare(next, best) == ::std::cmp::Ordering::Greater {
                                              next
                                          } else {
                                              best
                                          }
                                      });
-            Mutex::new(best_candidate.clone())
+            best_candidate.clone()
         };
 
         // Wrap the candidates in a structure that will let the eventual
@@ -157,14 +1167,44 @@ impl<Ctx: Context> Hive<Ctx> {
                                 .map(|c| RwLock::new(WorkingCandidate::new(c, hive.retries)))
                                 .collect::<Vec<RwLock<WorkingCandidate<Ctx::Solution>>>>();
 
-        Ok(Hive {
+        Hive::assemble(hive, rngs, working, best, evaluations, 0).archive_field()
+    }
+
+    /// Offers each candidate in the field to the Pareto archive.
+    fn archive_field(self) -> AbcResult<Hive<Ctx>> {
+        for candidate in try!(self.current_working()) {
+            try!(self.update_archive(&candidate));
+        }
+        Ok(self)
+    }
+
+    fn assemble(hive: HiveBuilder<Ctx>,
+                rngs: Vec<Mutex<HiveRng>>,
+                working: Vec<RwLock<WorkingCandidate<Ctx::Solution>>>,
+                best: Candidate<Ctx::Solution>,
+                evaluations: usize,
+                rounds: usize)
+                -> Hive<Ctx> {
+        let phi_scale = hive.phi_scale;
+        Hive {
             hive: hive,
             working: working,
-            best: best,
+            best: Mutex::new(best),
             scouting: RwLock::new(BTreeSet::new()),
+            rngs: rngs,
+            evaluations: AtomicUsize::new(evaluations),
+            budget: AtomicUsize::new(usize::MAX),
+            rounds: AtomicUsize::new(rounds),
+            phi_scale: AtomicU64::new(phi_scale.to_bits()),
+            explored: AtomicUsize::new(0),
+            improved: AtomicUsize::new(0),
+            selections: Mutex::new(Vec::new()),
+            exhausted: Mutex::new(BTreeSet::new()),
+            archive: Mutex::new(Vec::new()),
             tasks: Mutex::new(None),
+            condition: Mutex::new(None),
             sender: None,
-        })
+        }
     }
 
     /// Clone a snapshot of the current set of working candidates.
@@ -196,102 +1236,412 @@ impl<Ctx: Context> Hive<Ctx> {
     /// Perform greedy selection between a new candidate and the current best.
     fn consider_improvement(&self, candidate: &Candidate<Ctx::Solution>) -> AbcResult<()> {
         let mut best_guard = try!(self.best.lock());
-        if candidate.fitness > best_guard.fitness {
+        if compare(candidate, &best_guard) == ::std::cmp::Ordering::Greater {
             *best_guard = candidate.clone();
-            if let Some(mutex) = self.sender.as_ref() {
-                // We're streaming, so we need to post the improved candidate.
-                let sender_guard = try!(mutex.lock());
-                // If this errors, the receiver was dropped, so we're done.
-                if let Err(_) = sender_guard.send(candidate.clone()) {
-                    try!(self.stop());
-                }
+            for observer in &self.hive.hive_observers {
+                observer.new_best(candidate);
+            }
+            if self.hive.archive_capacity.is_none() {
+                try!(self.post(candidate));
             }
         }
         Ok(())
     }
 
-    fn work_on(&self, current_working: &[Candidate<Ctx::Solution>], n: usize) -> AbcResult<()> {
-        let variant_solution = self.hive.context.explore(current_working, n);
-        let variant_fitness = self.hive.context.evaluate_fitness(&variant_solution);
-        let variant = Candidate::new(variant_solution, variant_fitness);
+    /// Sends a candidate to the stream, if the hive is streaming.
+    fn post(&self, candidate: &Candidate<Ctx::Solution>) -> AbcResult<()> {
+        if let Some(mutex) = self.sender.as_ref() {
+            let sender_guard = try!(mutex.lock());
+            // If this errors, the receiver was dropped, so we're done.
+            if let Err(_) = sender_guard.send(candidate.clone()) {
+                try!(self.stop());
+            }
+        }
+        Ok(())
+    }
+
+    /// Offers a newly evaluated candidate to the Pareto archive, if the hive
+    /// keeps one.
+    ///
+    /// Infeasible candidates are never archived. If the hive is streaming,
+    /// the candidate is sent on once it has been archived.
+    fn update_archive(&self, candidate: &Candidate<Ctx::Solution>) -> AbcResult<()> {
+        let capacity = match self.hive.archive_capacity {
+            Some(capacity) if candidate.is_feasible() => capacity,
+            _ => return Ok(()),
+        };
+        let direction = self.hive.context.direction();
+        let archived = {
+            let mut archive_guard = try!(self.archive.lock());
+            pareto::insert(&mut archive_guard, candidate, capacity, direction)
+        };
+        if archived {
+            try!(self.post(candidate));
+        }
+        Ok(())
+    }
+
+    /// Claims `count` fitness evaluations from the hive's budget, either all
+    /// of them or none.
+    ///
+    /// Returns `false` if there is not enough left, in which case the caller
+    /// must not evaluate anything. Claiming the last evaluation in the budget
+    /// stops the hive.
+    fn claim_evaluations(&self, count: usize) -> AbcResult<bool> {
+        let budget = self.budget.load(Ordering::SeqCst);
+        let mut spent = self.evaluations.load(Ordering::SeqCst);
+        loop {
+            if spent >= budget || budget - spent < count {
+                try!(self.stop());
+                return Ok(false);
+            }
+            match self.evaluations
+                      .compare_exchange(spent, spent + count, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => break,
+                Err(actual) => spent = actual,
+            }
+        }
+        if spent + count >= budget {
+            try!(self.stop());
+        }
+        Ok(true)
+    }
+
+    /// Explores near the candidate at `n`, and applies the variant.
+    ///
+    /// `best` is the calling thread's copy of the best candidate, which is
+    /// only cloned again once a better candidate has replaced it.
+    fn work_on(&self,
+               current_working: &[Candidate<Ctx::Solution>],
+               n: usize,
+               bee: Bee,
+               best: &mut Option<Candidate<Ctx::Solution>>,
+               rng: &mut HiveRng)
+               -> AbcResult<()> {
+        {
+            // The best candidate only ever improves, so the copy is stale
+            // exactly when the hive's best beats it.
+            let best_guard = try!(self.get());
+            let stale = match *best {
+                Some(ref best) => compare(&best_guard, best) == ::std::cmp::Ordering::Greater,
+                None => true,
+            };
+            if stale {
+                *best = Some(best_guard.clone());
+            }
+        }
+        let exploration = Exploration {
+            field: current_working,
+            index: n,
+            best: best.as_ref().unwrap(),
+            modification_rate: self.hive.modification_rate,
+            phi_scale: self.phi_scale(),
+        };
+        let variant = self.hive.explore(&exploration, rng).and_then(|variant_solution| {
+            self.hive.evaluate(variant_solution, &|count| self.claim_evaluations(count))
+        });
+
+        // A failure that the policy tolerates counts as a variant that
+        // didn't improve.
+        let variant = match variant {
+            Ok(Some(variant)) => Some(variant),
+            Ok(None) => return Ok(()),
+            Err(error) => {
+                try!(self.hive.tolerate(error, &|count| self.claim_evaluations(count)));
+                None
+            }
+        };
+        self.apply(n, bee, variant, rng)
+    }
+
+    /// Replaces the candidate at `n` with `variant` if it is an improvement,
+    /// and otherwise depletes the candidate, scouting if it is exhausted.
+    ///
+    /// A variant of `None` stands for a failure that the policy tolerates.
+    /// Unless the scout policy is immediate, exhausted candidates are left
+    /// for the scout phase at the end of the round.
+    fn apply(&self,
+             n: usize,
+             bee: Bee,
+             variant: Option<Candidate<Ctx::Solution>>,
+             rng: &mut HiveRng)
+             -> AbcResult<()> {
+        self.explored.fetch_add(1, Ordering::Relaxed);
+        let defer = self.hive.scout_policy() != ScoutPolicy::Immediate;
+        if let Some(ref variant) = variant {
+            try!(self.update_archive(variant));
+        }
+
         let mut write_guard = try!(self.working[n].write());
-        if variant.fitness > write_guard.candidate.fitness {
-            *write_guard = WorkingCandidate::new(variant, self.hive.retries);
+        let improved = match variant {
+            Some(ref variant) => self.hive.improves(variant, &write_guard.candidate, rng),
+            None => false,
+        };
+        if improved {
+            self.improved.fetch_add(1, Ordering::Relaxed);
+            *write_guard = WorkingCandidate::new(variant.unwrap(), self.hive.retries);
+            for observer in &self.hive.hive_observers {
+                observer.candidate_improved(n, bee, &write_guard.candidate);
+            }
             try!(self.consider_improvement(&write_guard.candidate));
+            if defer {
+                try!(self.exhausted.lock()).remove(&n);
+            }
         } else {
             write_guard.deplete();
+            let abandon = write_guard.expired() ||
+                          (variant.is_none() && self.hive.failure == FailurePolicy::Rescout);
+            if abandon && defer {
+                try!(self.exhausted.lock()).insert(n);
+                return Ok(());
+            }
             // Scouting has been folded into the working process
-            if write_guard.expired() {
+            if abandon && try!(self.claim_evaluations(0)) {
                 {
                     let mut scouting_guard = try!(self.scouting.write());
                     scouting_guard.insert(n);
                 }
                 drop(write_guard);
+                try!(self.scout(n, rng));
+            }
+        }
+        Ok(())
+    }
 
-                let candidate = self.hive.new_candidate();
-                try!(self.consider_improvement(&candidate));
-                {
-                    let mut write_guard = try!(self.working[n].write());
-                    *write_guard = WorkingCandidate::new(candidate, self.hive.retries);
+    /// Abandons exhausted candidates at the end of a round, according to the
+    /// scout policy.
+    fn scout_phase(&self, rng: &mut HiveRng) -> AbcResult<()> {
+        // Copy the exhausted candidates, rather than hold the lock while
+        // reading them, since `apply` takes the locks in the other order.
+        let exhausted = try!(self.exhausted.lock()).clone();
+        let abandoned = match self.hive.scout_policy() {
+            ScoutPolicy::Immediate => return Ok(()),
+            ScoutPolicy::AllExhaustedPerCycle => exhausted.into_iter().collect(),
+            ScoutPolicy::OnePerCycle => {
+                let mut most_exhausted = None;
+                for n in exhausted {
+                    let retries = try!(self.working[n].read()).retries();
+                    most_exhausted = match most_exhausted {
+                        Some((_, fewest)) if fewest <= retries => most_exhausted,
+                        _ => Some((n, retries)),
+                    };
                 }
+                most_exhausted.into_iter().map(|(n, _)| n).collect::<Vec<usize>>()
+            }
+        };
 
-                let mut scouting_guard = try!(self.scouting.write());
-                scouting_guard.remove(&n);
+        for n in abandoned {
+            try!(self.exhausted.lock()).remove(&n);
+            if !try!(self.claim_evaluations(0)) {
+                break;
+            }
+            try!(self.scouting.write()).insert(n);
+            if !try!(self.scout(n, rng)) {
+                break;
             }
         }
         Ok(())
     }
 
-    fn choose(&self, current_working: &[Candidate<Ctx::Solution>]) -> AbcResult<usize> {
-        let fitnesses = (self.hive.scale)(current_working.iter()
-                                                         .map(|candidate| candidate.fitness)
-                                                         .collect::<Vec<f64>>());
-
-        // Avoid observing candidates that are being scouted.
-        let running_totals = {
-            let scouting_guard = try!(self.scouting.read());
-            fitnesses.iter()
-                     .enumerate()
-                     .filter(|&(ref i, _)| !scouting_guard.contains(i))
-                     .scan(0f64, |total, (i, fitness)| {
-                         *total += *fitness;
-                         Some((i, *total))
-                     })
-                     .collect::<Vec<(usize, f64)>>()
+    /// Replaces the candidate at `n`, which must already be marked as being
+    /// scouted, with a new one.
+    ///
+    /// Returns `false`, leaving the candidate in place, if the evaluation
+    /// budget ran out first.
+    fn scout(&self, n: usize, rng: &mut HiveRng) -> AbcResult<bool> {
+        let claim = |count| self.claim_evaluations(count);
+        let candidate = match try!(self.hive.new_candidate(rng, &claim)) {
+            Some(candidate) => candidate,
+            None => {
+                try!(self.scouting.write()).remove(&n);
+                return Ok(false);
+            }
         };
+        try!(self.consider_improvement(&candidate));
+        try!(self.update_archive(&candidate));
+        {
+            let mut write_guard = try!(self.working[n].write());
+            for observer in &self.hive.hive_observers {
+                observer.candidate_abandoned(n, &write_guard.candidate, &candidate);
+            }
+            *write_guard = WorkingCandidate::new(candidate, self.hive.retries);
+        }
 
-        // Multiplying the choice point is equivalent to, and more efficient than, normalizing
-        // all of the scaled fitnesses and having a choice point in [0,1)
-        match running_totals.last() {
-            Some(&(_, total_fitness)) => {
-                let choice_point = thread_rng().next_f64() * total_fitness;
-                for &(i, total) in &running_totals {
-                    if total > choice_point {
-                        return Ok(i);
-                    }
+        let mut scouting_guard = try!(self.scouting.write());
+        scouting_guard.remove(&n);
+        Ok(true)
+    }
+
+    /// Runs one phase of a synchronous generation, in which a bee works on
+    /// each of `indices`.
+    ///
+    /// The variants are all explored from the same field, then evaluated in
+    /// one batch. Returns `false` if the evaluation budget ran out.
+    fn phase(&self, indices: &[usize], bee: Bee, rng: &mut HiveRng) -> AbcResult<bool> {
+        let claim = |count| self.claim_evaluations(count);
+        if !try!(claim(0)) {
+            return Ok(false);
+        }
+        let current_working = try!(self.current_working());
+        let best = try!(self.get()).clone();
+
+        let mut explored = Vec::with_capacity(indices.len());
+        let mut failed = Vec::new();
+        for &n in indices {
+            let exploration = Exploration {
+                field: &current_working,
+                index: n,
+                best: &best,
+                modification_rate: self.hive.modification_rate,
+                phi_scale: self.phi_scale(),
+            };
+            match self.hive.explore(&exploration, rng) {
+                Ok(solution) => explored.push((n, solution)),
+                Err(error) => {
+                    try!(self.hive.tolerate(error, &claim));
+                    failed.push(n);
                 }
-                unreachable!();
             }
+        }
+
+        let (sources, solutions): (Vec<usize>, Vec<Ctx::Solution>) = explored.into_iter().unzip();
+        let count = solutions.len();
+        let variants = try!(self.hive.evaluate_batch(solutions, &claim));
+        let exhausted = variants.len() < count;
+        let applied = sources.into_iter()
+                             .zip(variants)
+                             .chain(failed.into_iter().map(|n| (n, None)));
+        for (n, variant) in applied {
+            try!(self.apply(n, bee, variant, rng));
+        }
+        Ok(!exhausted)
+    }
+
+    /// Runs one synchronous generation, in the textbook order.
+    ///
+    /// First, every worker explores near its candidate. Then the observers'
+    /// probabilities are computed, once, from the field as the workers left
+    /// it, and every observer explores near its chosen candidate. The scout
+    /// phase follows once the round is complete.
+    fn generation(&self, rng: &mut HiveRng) -> AbcResult<()> {
+        let workers = (0..self.hive.workers).collect::<Vec<usize>>();
+        if !try!(self.phase(&workers, Bee::Worker, rng)) {
+            return Ok(());
+        }
 
-            // If we are currently scouting all of the solutions, pick one at random.
-            None => Ok(thread_rng().gen_range::<usize>(0, fitnesses.len())),
+        // Forget any choices left over from an asynchronous run, so that the
+        // observers choose from the field as the workers left it.
+        try!(self.selections.lock()).clear();
+        let current_working = try!(self.current_working());
+        let mut observed = Vec::with_capacity(self.hive.observers);
+        for _ in 0..self.hive.observers {
+            observed.push(try!(self.choose(&current_working, rng)));
         }
+        try!(self.phase(&observed, Bee::Observer, rng));
+        Ok(())
+    }
+
+    fn choose(&self,
+              current_working: &[Candidate<Ctx::Solution>],
+              rng: &mut HiveRng)
+              -> AbcResult<usize> {
+        let mut selections = try!(self.selections.lock());
+        let scouting = try!(self.scouting.read());
+        let weights = || {
+            let fitnesses = match self.hive.archive_capacity {
+                Some(_) => pareto::rank_fitnesses(current_working, self.hive.context.direction()),
+                None => {
+                    current_working.iter()
+                                   .map(|candidate| candidate.fitness)
+                                   .collect::<Vec<f64>>()
+                }
+            };
+            validate_weights((self.hive.scale)(fitnesses))
+        };
+        choose(&mut selections,
+               current_working,
+               weights,
+               &scouting,
+               &*self.hive.selection,
+               self.hive.observers,
+               rng)
     }
 
-    fn execute(&self, task: &Task) -> AbcResult<()> {
+    fn execute(&self,
+               task: &Task,
+               best: &mut Option<Candidate<Ctx::Solution>>,
+               rng: &mut HiveRng)
+               -> AbcResult<()> {
         let current_working = try!(self.current_working());
-        let index = match *task {
+        let (index, bee) = match *task {
             Task::Worker(n) => {
                 // If the worker's candidate is in the middle of being replaced, just skip it.
                 let scouting_guard = try!(self.scouting.read());
                 if scouting_guard.contains(&n) {
                     return Ok(());
                 }
-                n
+                (n, Bee::Worker)
+            }
+            Task::Observer(_) => (try!(self.choose(&current_working, rng)), Bee::Observer),
+        };
+        self.work_on(&current_working, index, bee, best, rng)
+    }
+
+    fn phi_scale(&self) -> f64 {
+        f64::from_bits(self.phi_scale.load(Ordering::Relaxed))
+    }
+
+    /// Applies the 1/5 success rule to the scale of phi.
+    fn adapt_phi(&self) {
+        let explored = self.explored.swap(0, Ordering::Relaxed);
+        let improved = self.improved.swap(0, Ordering::Relaxed);
+        if explored == 0 {
+            return;
+        }
+
+        let success = improved as f64 / explored as f64;
+        let scale = self.phi_scale();
+        let scale = if success < 0.2 {
+            scale * 0.85
+        } else if success > 0.2 {
+            scale / 0.85
+        } else {
+            scale
+        };
+        self.phi_scale.store(scale.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Checks the stop condition, if there is one, and stops the hive if it
+    /// has been met.
+    fn check_condition(&self) -> AbcResult<()> {
+        // Gather the status one lock at a time, since other threads can
+        // acquire the best-candidate and task locks in either order.
+        let round = match try!(self.get_round()) {
+            Some(round) => round,
+            None => return Ok(()),
+        };
+        let best_fitness = try!(self.get()).fitness;
+
+        let should_stop = {
+            let mut condition_guard = try!(self.condition.lock());
+            match condition_guard.as_mut() {
+                Some(stopping) => {
+                    let status = Status {
+                        round: round,
+                        evaluations: self.evaluations() - stopping.evaluations,
+                        best_fitness: best_fitness,
+                        elapsed: stopping.started.elapsed(),
+                    };
+                    stopping.condition.should_stop(&status)
+                }
+                None => false,
             }
-            Task::Observer(_) => try!(self.choose(&current_working)),
         };
-        self.work_on(&current_working, index)
+
+        if should_stop {
+            try!(self.stop());
+        }
+        Ok(())
     }
 
     fn run(&self, tasks: TaskGenerator) -> AbcResult<()> {
@@ -299,40 +1649,132 @@ impl<Ctx: Context> Hive<Ctx> {
             let mut guard = try!(self.tasks.lock());
             *guard = Some(tasks);
         }
+        let conditional = try!(self.condition.lock()).is_some();
+
+        let outcome = match self.hive.mode {
+            Mode::Asynchronous => self.run_threads(conditional),
+            Mode::Synchronous => self.run_generations(conditional),
+        };
+
+        // Returns `Ok(())` only if the run finished cleanly, and the task
+        // cycle is successfully cleared away.
+        //
+        // We avoid `try!` because we want all of the following logic to
+        // execute unconditionally.
+        try!(outcome.and(self.tasks
+                             .lock()
+                             .map(|mut tasks_guard| {
+                                 if let Some(tasks) = tasks_guard.take() {
+                                     self.rounds.fetch_add(tasks.round, Ordering::Relaxed);
+                                 }
+                             })
+                             .map_err(AbcError::from))
+                    .and(self.condition
+                             .lock()
+                             .map(|mut condition_guard| *condition_guard = None)
+                             .map_err(AbcError::from)));
+
+        if !self.hive.hive_observers.is_empty() {
+            let best = try!(self.get()).clone();
+            let statistics = self.statistics();
+            for observer in &self.hive.hive_observers {
+                observer.run_finished(&best, &statistics);
+            }
+        }
+        Ok(())
+    }
 
+    /// Works through the tasks asynchronously, on each of the hive's threads.
+    fn run_threads(&self, conditional: bool) -> AbcResult<()> {
         let mut handles: Vec<ScopedJoinHandle<AbcResult<()>>> = Vec::new();
 
         scope(|scope| {
-            for _ in 0..self.hive.threads {
-                handles.push(scope.spawn(|| {
+            for rng_mutex in &self.rngs {
+                handles.push(scope.spawn(move || {
+                    let mut rng = try!(rng_mutex.lock());
+                    let mut best = None;
                     loop {
-                        let task = {
+                        if conditional {
+                            try!(self.check_condition());
+                        }
+
+                        let (task, completed_round) = {
                             let mut guard = try!(self.tasks.lock());
-                            guard.as_mut().and_then(|gen| gen.next())
+                            match guard.as_mut() {
+                                Some(gen) => {
+                                    let round = gen.round;
+                                    let task = gen.next();
+                                    let completed = if gen.round > round {
+                                        Some(round)
+                                    } else {
+                                        None
+                                    };
+                                    (task, completed)
+                                }
+                                None => (None, None),
+                            }
                         };
 
-                        match task {
-                            Some(t) => try!(self.execute(&t)),
-                            None => return Ok(()),
+                        let result = match completed_round {
+                            Some(round) => self.complete_round(round, &mut rng),
+                            None => Ok(()),
                         };
+                        let result = match task {
+                            Some(t) => result.and_then(|_| self.execute(&t, &mut best, &mut rng)),
+                            None => return result,
+                        };
+                        if let Err(error) = result {
+                            // Bring the other threads to a halt before
+                            // reporting the error.
+                            let _ = self.stop();
+                            return Err(error);
+                        }
                     }
                 }));
             }
 
-            // Returns `Ok(())` only if all threads join cleanly, and the task
-            // cycle is successfully cleared away.
-            //
-            // We avoid `try!` because we want all of the following logic to
-            // execute unconditionally.
+            // Returns `Ok(())` only if all threads join cleanly.
             handles.drain(..)
                    .fold(Ok(()), |result, handle| result.and(handle.join()))
-                   .and(self.tasks
-                            .lock()
-                            .map(|mut tasks_guard| *tasks_guard = None)
-                            .map_err(AbcError::from))
         })
     }
 
+    /// Works through the tasks a generation at a time, on the calling thread.
+    fn run_generations(&self, conditional: bool) -> AbcResult<()> {
+        let mut rng = try!(self.rngs[0].lock());
+        loop {
+            if conditional {
+                try!(self.check_condition());
+            }
+
+            let round = match try!(self.tasks.lock()).as_mut() {
+                Some(gen) => gen.next_round(),
+                None => None,
+            };
+            match round {
+                Some(round) => {
+                    try!(self.generation(&mut rng));
+                    try!(self.complete_round(round, &mut rng));
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Handles the scouting and bookkeeping for the end of a round.
+    fn complete_round(&self, round: usize, rng: &mut HiveRng) -> AbcResult<()> {
+        try!(self.scout_phase(rng));
+        if let Some(period) = self.hive.phi_period {
+            if round % period == period - 1 {
+                self.adapt_phi();
+            }
+        }
+        for observer in &self.hive.hive_observers {
+            observer.round_completed(round);
+        }
+        Ok(())
+    }
+
     /// Runs for a fixed number of rounds, then return the best solution found.
     ///
     /// If one of the worker threads panics while working, this will return
@@ -343,6 +1785,52 @@ impl<Ctx: Context> Hive<Ctx> {
         self.get().map(|guard| guard.clone())
     }
 
+    /// Runs for a fixed number of fitness evaluations, then return the best
+    /// solution found.
+    ///
+    /// The budget is shared by all of the worker threads, and the hive stops
+    /// once exactly `evaluations` more evaluations have been performed. This
+    /// makes it possible to compare runs by the number of solutions passed to
+    /// the context for evaluation, as is customary in the literature, rather
+    /// than by rounds. Retries count as evaluations; solutions answered by the
+    /// [evaluation cache](struct.HiveBuilder.html#method.set_evaluation_cache)
+    /// do not.
+    ///
+    /// If one of the worker threads panics while working, this will return
+    /// `Err(abc::Error)`. Otherwise, it will return `Ok` with a `Candidate`.
+    pub fn run_for_evaluations(&self, evaluations: usize) -> AbcResult<Candidate<Ctx::Solution>> {
+        if evaluations == 0 {
+            return self.get().map(|guard| guard.clone());
+        }
+        let budget = self.evaluations().saturating_add(evaluations);
+        self.budget.store(budget, Ordering::SeqCst);
+        let tasks = TaskGenerator::new(self.hive.workers, self.hive.observers);
+        let result = self.run(tasks);
+        self.budget.store(usize::MAX, Ordering::SeqCst);
+        try!(result);
+        self.get().map(|guard| guard.clone())
+    }
+
+    /// Runs until `condition` is met, then returns the best solution found.
+    ///
+    /// See the [`stop`](stop/index.html) module for the available conditions.
+    ///
+    /// If one of the worker threads panics while working, this will return
+    /// `Err(abc::Error)`. Otherwise, it will return `Ok` with a `Candidate`.
+    pub fn run_until(&self, condition: Box<StopCondition>) -> AbcResult<Candidate<Ctx::Solution>> {
+        {
+            let mut condition_guard = try!(self.condition.lock());
+            *condition_guard = Some(Stopping {
+                condition: condition,
+                started: Instant::now(),
+                evaluations: self.evaluations(),
+            });
+        }
+        let tasks = TaskGenerator::new(self.hive.workers, self.hive.observers);
+        try!(self.run(tasks));
+        self.get().map(|guard| guard.clone())
+    }
+
     /// Run indefinitely.
     ///
     /// If one of the worker threads panics while working, this will return
@@ -362,15 +1850,35 @@ impl<Ctx: Context> Hive<Ctx> {
 
     /// Each new best candidate will be sent to `sender`.
     ///
+    /// If the hive keeps a
+    /// [Pareto archive](struct.HiveBuilder.html#method.set_pareto_archive),
+    /// each candidate that enters the archive is sent instead, starting with
+    /// the archive's current members.
+    ///
     /// This is kept in a separate function so that the hive can be borrowed
     /// while running.
     pub fn set_sender(&mut self, sender: Sender<Candidate<Ctx::Solution>>) {
-        if let Ok(best_guard) = self.best.lock() {
+        if self.hive.archive_capacity.is_some() {
+            if let Ok(archive_guard) = self.archive.lock() {
+                for member in archive_guard.iter() {
+                    sender.send(member.clone()).unwrap_or(());
+                }
+            }
+        } else if let Ok(best_guard) = self.best.lock() {
             sender.send(best_guard.clone()).unwrap_or(());
         }
         self.sender = Some(Mutex::new(sender));
     }
 
+    /// Returns a copy of the non-dominated candidates in the Pareto archive.
+    ///
+    /// Unless the hive was built with
+    /// [`set_pareto_archive`](struct.HiveBuilder.html#method.set_pareto_archive),
+    /// this is empty.
+    pub fn archive(&self) -> AbcResult<Vec<Candidate<Ctx::Solution>>> {
+        self.archive.lock().map(|guard| guard.clone()).map_err(AbcError::from)
+    }
+
     /// Returns the current round of a running hive.
     ///
     /// If a worker thread has panicked and poisoned the task generator lock,
@@ -385,10 +1893,111 @@ impl<Ctx: Context> Hive<Ctx> {
         Ok(tasks_guard.as_ref().map(|tasks| tasks.round))
     }
 
+    /// Returns the number of fitness evaluations performed so far.
+    ///
+    /// This includes the evaluations of the initial candidates, and of the
+    /// new candidates found by scouts, but not the solutions answered by the
+    /// evaluation cache.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.load(Ordering::SeqCst)
+    }
+
+    /// Returns running totals of the work done by the hive.
+    pub fn statistics(&self) -> Statistics {
+        let (cache_hits, cache_misses) = self.hive.cache.as_ref().map_or((0, 0), |c| c.counts());
+        Statistics {
+            rounds: self.rounds.load(Ordering::Relaxed),
+            evaluations: self.evaluations(),
+            cache_hits: cache_hits,
+            cache_misses: cache_misses,
+        }
+    }
+
+    /// Saves the state of the hive, so that it can be resumed later.
+    ///
+    /// This can be called while the hive is running, though the candidates
+    /// may then change while the snapshot is being taken. See
+    /// [`Snapshot`](struct.Snapshot.html).
+    pub fn snapshot(&self) -> AbcResult<Snapshot<Ctx::Solution>> {
+        let mut candidates = Vec::with_capacity(self.working.len());
+        let mut retries = Vec::with_capacity(self.working.len());
+        for candidate_mutex in &self.working {
+            let read_guard = try!(candidate_mutex.read());
+            candidates.push(read_guard.candidate.clone());
+            retries.push(read_guard.retries());
+        }
+        let statistics = self.statistics();
+
+        Ok(Snapshot {
+            candidates: candidates,
+            retries: retries,
+            best: try!(self.get()).clone(),
+            archive: try!(self.archive()),
+            rounds: statistics.rounds,
+            evaluations: statistics.evaluations,
+            phi_scale: self.phi_scale(),
+        })
+    }
+
     /// Get a reference to the hive's context.
     pub fn context(&self) -> &Ctx {
         &self.hive.context
     }
+
+    /// Returns copies of the `count` fittest working candidates, fittest
+    /// first.
+    ///
+    /// These are the candidates that an
+    /// [`Archipelago`](struct.Archipelago.html) sends to other islands.
+    pub fn emigrants(&self, count: usize) -> AbcResult<Vec<Candidate<Ctx::Solution>>> {
+        let mut candidates = try!(self.current_working());
+        candidates.sort_by(|a, b| compare(b, a));
+        candidates.truncate(count);
+        Ok(candidates)
+    }
+
+    /// Places candidates from elsewhere in the field.
+    ///
+    /// Each immigrant replaces the least fit working candidate, as long as
+    /// the immigrant is fitter, by the feasibility rules; the others are
+    /// turned away. Immigrants arrive
+    /// with a full set of retries. Candidates that are being scouted are left
+    /// alone.
+    pub fn immigrate(&self, immigrants: &[Candidate<Ctx::Solution>]) -> AbcResult<()> {
+        for immigrant in immigrants {
+            let mut worst: Option<(usize, Candidate<Ctx::Solution>)> = None;
+            {
+                let scouting_guard = try!(self.scouting.read());
+                for (n, candidate_lock) in self.working.iter().enumerate() {
+                    if scouting_guard.contains(&n) {
+                        continue;
+                    }
+                    let read_guard = try!(candidate_lock.read());
+                    let least_fit = match worst {
+                        Some((_, ref least)) => {
+                            compare(&read_guard.candidate, least) == ::std::cmp::Ordering::Less
+                        }
+                        None => true,
+                    };
+                    if least_fit {
+                        worst = Some((n, read_guard.candidate.clone()));
+                    }
+                }
+            }
+
+            let n = match worst {
+                Some((n, ref least)) if compare(immigrant, least) ==
+                                        ::std::cmp::Ordering::Greater => n,
+                _ => continue,
+            };
+            try!(self.consider_improvement(immigrant));
+            try!(self.update_archive(immigrant));
+            *try!(self.working[n].write()) = WorkingCandidate::new(immigrant.clone(),
+                                                                   self.hive.retries);
+            try!(self.exhausted.lock()).remove(&n);
+        }
+        Ok(())
+    }
 }
 
 impl<Ctx: Context + 'static> Hive<Ctx> {
@@ -396,7 +2005,11 @@ impl<Ctx: Context + 'static> Hive<Ctx> {
     ///
     /// This method consumes the hive, which will run until the `HiveBuilder`
     /// object is dropped. It returns an `mpsc::Receiver`, which receives a
-    /// `Candidate` each time the hive improves on its best solution.
+    /// `Candidate` each time the hive improves on its best solution. A hive
+    /// that keeps a
+    /// [Pareto archive](struct.HiveBuilder.html#method.set_pareto_archive)
+    /// sends each candidate that enters the archive instead, so that the
+    /// receiver sees every new trade-off as it is found.
     pub fn stream(mut self) -> Receiver<Candidate<Ctx::Solution>> {
         let (sender, receiver) = channel();
         spawn(move || {
@@ -426,3 +2039,800 @@ impl<Ctx: Context> Drop for Hive<Ctx> {
         self.stop().unwrap_or(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::Walk;
+
+    fn seeded_run(seed: u64) -> Vec<i32> {
+        let hive = HiveBuilder::new(Walk, 5).set_threads(1).set_seed(seed).build().unwrap();
+        hive.run_for_rounds(20).unwrap();
+        hive.current_working().unwrap().iter().map(|c| c.solution).collect()
+    }
+
+    #[test]
+    fn same_seed_same_run() {
+        assert_eq!(seeded_run(42), seeded_run(42));
+    }
+
+    #[test]
+    fn different_seeds_different_runs() {
+        assert!(seeded_run(1) != seeded_run(2));
+    }
+
+    #[test]
+    fn evaluation_budget_is_exact() {
+        let hive = HiveBuilder::new(Walk, 5).set_threads(4).build().unwrap();
+        assert_eq!(hive.evaluations(), 5);
+        hive.run_for_evaluations(103).unwrap();
+        assert_eq!(hive.evaluations(), 108);
+        hive.run_for_evaluations(7).unwrap();
+        assert_eq!(hive.statistics().evaluations, 115);
+    }
+
+    #[test]
+    fn observer_sees_rounds_and_finish() {
+        use std::sync::Arc;
+
+        #[derive(Default)]
+        struct Counts {
+            rounds: AtomicUsize,
+            improvements: AtomicUsize,
+            finished: AtomicUsize,
+        }
+
+        struct Counter(Arc<Counts>);
+
+        impl HiveObserver<i32> for Counter {
+            fn round_completed(&self, _: usize) {
+                self.0.rounds.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn candidate_improved(&self, _: usize, _: Bee, _: &Candidate<i32>) {
+                self.0.improvements.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn run_finished(&self, _: &Candidate<i32>, statistics: &Statistics) {
+                assert_eq!(statistics.rounds, 10);
+                self.0.finished.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let counts = Arc::new(Counts::default());
+        let hive = HiveBuilder::new(Walk, 5)
+                       .set_threads(2)
+                       .add_hive_observer(Box::new(Counter(counts.clone())))
+                       .build()
+                       .unwrap();
+        hive.run_for_rounds(10).unwrap();
+        assert_eq!(counts.rounds.load(Ordering::SeqCst), 10);
+        assert!(counts.improvements.load(Ordering::SeqCst) > 0);
+        assert_eq!(counts.finished.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn resume_from_snapshot() {
+        let hive = HiveBuilder::new(Walk, 5).set_threads(2).build().unwrap();
+        hive.run_for_rounds(10).unwrap();
+        let snapshot = hive.snapshot().unwrap();
+        assert_eq!(snapshot.rounds, 10);
+
+        let resumed = HiveBuilder::new(Walk, 1).resume_from(snapshot.clone()).build().unwrap();
+        let candidates = resumed.current_working().unwrap();
+        assert_eq!(candidates.len(), 5);
+        assert!(candidates.iter()
+                          .zip(&snapshot.candidates)
+                          .all(|(x, y)| x.solution == y.solution));
+        assert_eq!(resumed.get().unwrap().solution, snapshot.best.solution);
+        assert_eq!(resumed.statistics(), hive.statistics());
+    }
+
+    #[test]
+    fn only_whole_round_selections_are_batched() {
+        let roulette = HiveBuilder::new(Walk, 5).set_observers(4).set_seed(2).build().unwrap();
+        let field = roulette.current_working().unwrap();
+        let mut rng = derive_rng(2, 0);
+        roulette.choose(&field, &mut rng).unwrap();
+        assert!(roulette.selections.lock().unwrap().is_empty());
+
+        let universal = HiveBuilder::new(Walk, 5)
+                            .set_observers(4)
+                            .set_seed(2)
+                            .set_selection(::selection::stochastic_universal())
+                            .build()
+                            .unwrap();
+        universal.choose(&field, &mut rng).unwrap();
+        assert_eq!(universal.selections.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn inconsistent_snapshots_are_rejected() {
+        let hive = HiveBuilder::new(Walk, 5).build().unwrap();
+        let snapshot = hive.snapshot().unwrap();
+
+        let mut short = snapshot.clone();
+        short.retries.pop();
+        let mut empty = snapshot.clone();
+        empty.candidates.clear();
+        empty.retries.clear();
+        let mut flat = snapshot;
+        flat.phi_scale = 0.0;
+
+        for broken in vec![short, empty, flat] {
+            match HiveBuilder::new(Walk, 1).resume_from(broken).build() {
+                Err(AbcError::InvalidConfiguration(_)) => {}
+                other => panic!("Expected an invalid configuration, got {:?}", other.is_ok()),
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn snapshots_survive_serialization() {
+        use serde_json;
+
+        let hive = HiveBuilder::new(Walk, 5).set_seed(4).build().unwrap();
+        hive.run_for_rounds(10).unwrap();
+        let snapshot = hive.snapshot().unwrap();
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: Snapshot<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.retries, snapshot.retries);
+        assert_eq!(restored.rounds, snapshot.rounds);
+        assert_eq!(restored.evaluations, snapshot.evaluations);
+        assert_eq!(restored.best.solution, snapshot.best.solution);
+        assert!(restored.candidates
+                        .iter()
+                        .zip(&snapshot.candidates)
+                        .all(|(x, y)| x.solution == y.solution && x.fitness == y.fitness));
+
+        let resumed = HiveBuilder::new(Walk, 1).resume_from(restored).build().unwrap();
+        assert_eq!(resumed.statistics(), hive.statistics());
+    }
+
+    #[test]
+    fn run_until_target() {
+        use stop;
+        let hive = HiveBuilder::new(Walk, 5).set_threads(2).build().unwrap();
+        let best = hive.run_until(stop::target_fitness(150.0)).unwrap();
+        assert!(best.fitness >= 150.0);
+    }
+
+    // Walks upwards, but can't evaluate anything beyond 120.
+    struct Cliff;
+
+    impl Context for Cliff {
+        type Solution = i32;
+
+        walk_moves!();
+
+        fn evaluate_fitness(&self, solution: &i32) -> f64 {
+            if *solution > 120 { f64::NAN } else { *solution as f64 }
+        }
+    }
+
+    #[test]
+    fn nan_fitness_is_an_error() {
+        let hive = HiveBuilder::new(Cliff, 5).set_threads(2).build().unwrap();
+        match hive.run_for_rounds(100) {
+            Err(AbcError::InvalidFitness(fitness)) => assert!(fitness.is_nan()),
+            other => panic!("Expected an invalid fitness, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nan_fitness_as_worst() {
+        let hive = HiveBuilder::new(Cliff, 5)
+                       .set_threads(2)
+                       .set_nan_as_worst(true)
+                       .build()
+                       .unwrap();
+        let best = hive.run_for_rounds(100).unwrap();
+        assert!(best.solution <= 120);
+        assert!(hive.current_working().unwrap().iter().all(|c| c.fitness.is_finite()));
+    }
+
+    #[test]
+    fn feasible_candidates_are_weighted_first() {
+        let field = [(3.0, 0.0), (1.0, 0.0), (0.0, 1.0), (0.0, 3.0)]
+                        .iter()
+                        .map(|&(fitness, violation)| {
+                            let mut candidate = Candidate::new((), fitness);
+                            candidate.violation = violation;
+                            candidate
+                        })
+                        .collect::<Vec<Candidate<()>>>();
+        assert_eq!(feasibility_weights(&field, vec![3.0, 1.0, 0.0, 0.0]),
+                   vec![0.875, 0.625, 0.375, 0.125]);
+        assert_eq!(feasibility_weights(&field[..2], vec![3.0, 1.0]), vec![3.0, 1.0]);
+    }
+
+    #[test]
+    fn weights_are_validated() {
+        assert_eq!(validate_weights(vec![-2.0, 1.0, f64::NEG_INFINITY]).unwrap(),
+                   vec![0.0, 3.0, 0.0]);
+        assert_eq!(validate_weights(vec![2.0, 1.0]).unwrap(), vec![2.0, 1.0]);
+        assert!(validate_weights(vec![1.0, f64::NAN]).is_err());
+        assert!(validate_weights(vec![1.0, f64::INFINITY]).is_err());
+    }
+
+    #[test]
+    fn negative_fitnesses_are_observed() {
+        let hive = HiveBuilder::new(Cliff, 5)
+                       .set_threads(1)
+                       .set_nan_as_worst(true)
+                       .set_scaling(::scaling::proportionate())
+                       .build()
+                       .unwrap();
+        let mut field = hive.current_working().unwrap();
+        for (i, candidate) in field.iter_mut().enumerate() {
+            candidate.fitness = i as f64 - 1000.0;
+        }
+
+        // Once shifted, the least fit candidate has no weight at all.
+        let mut rng = HiveRng::from_seed([1, 2, 3, 4]);
+        for _ in 0..50 {
+            assert!(hive.choose(&field, &mut rng).unwrap() != 0);
+        }
+    }
+
+    struct Fragile;
+
+    impl Context for Fragile {
+        type Solution = i32;
+
+        thread_rng_moves!(i32);
+
+        fn make_with_rng(&self, rng: &mut HiveRng) -> i32 {
+            ::testing::start(rng)
+        }
+
+        fn evaluate_fitness(&self, solution: &i32) -> f64 {
+            *solution as f64
+        }
+
+        fn explore_with_rng(&self, field: &[Candidate<i32>], n: usize, _: &mut HiveRng) -> i32 {
+            if field[n].solution > 110 {
+                panic!("Lost at {}", field[n].solution);
+            }
+            field[n].solution + 5
+        }
+    }
+
+    #[test]
+    fn context_panics_are_reported() {
+        let hive = HiveBuilder::new(Fragile, 5).set_threads(2).build().unwrap();
+        match hive.run_for_rounds(100) {
+            Err(AbcError::Panicked { method, message }) => {
+                assert_eq!(method, "explore");
+                assert!(message.starts_with("Lost at "));
+            }
+            other => panic!("Expected a panic, got {:?}", other),
+        }
+
+        // The hive's locks survive the panic.
+        assert!(hive.get().is_ok());
+        assert!(hive.snapshot().is_ok());
+    }
+
+    #[test]
+    fn invalid_configuration() {
+        match HiveBuilder::new(Walk, 5).set_threads(0).build() {
+            Err(AbcError::InvalidConfiguration(_)) => {}
+            other => panic!("Expected an invalid configuration, got {:?}", other.is_ok()),
+        }
+    }
+
+    // Fails on every other evaluation, and always beyond 110.
+    #[derive(Default)]
+    struct Simulator {
+        calls: AtomicUsize,
+    }
+
+    impl Context for Simulator {
+        type Solution = i32;
+
+        walk_moves!();
+
+        fn evaluate_fitness(&self, solution: &i32) -> f64 {
+            *solution as f64
+        }
+
+        fn try_evaluate_objective(&self, solution: &i32) -> ContextResult<f64> {
+            if *solution > 110 {
+                Err(From::from(format!("{} is out of range", solution)))
+            } else if self.calls.fetch_add(1, Ordering::SeqCst) % 2 == 1 {
+                Err(From::from("flaky"))
+            } else {
+                Ok(self.evaluate_fitness(solution))
+            }
+        }
+    }
+
+    #[test]
+    fn failures_abort_by_default() {
+        match HiveBuilder::new(Simulator::default(), 5).set_threads(1).build() {
+            Err(AbcError::User(error)) => assert_eq!(error.to_string(), "flaky"),
+            other => panic!("Expected a user error, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn failures_are_retried() {
+        let hive = HiveBuilder::new(Simulator::default(), 5)
+                       .set_threads(1)
+                       .set_failure_policy(FailurePolicy::Retry(1))
+                       .build()
+                       .unwrap();
+        match hive.run_for_rounds(100) {
+            Err(AbcError::User(error)) => assert!(error.to_string().ends_with("out of range")),
+            other => panic!("Expected a user error, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn retries_count_as_evaluations() {
+        let hive = HiveBuilder::new(Simulator::default(), 5)
+                       .set_threads(1)
+                       .set_seed(2)
+                       .set_failure_policy(FailurePolicy::Retry(1))
+                       .build()
+                       .unwrap();
+        let initial = hive.evaluations();
+        assert_eq!(initial, hive.context().calls.load(Ordering::SeqCst));
+        hive.run_for_evaluations(9).unwrap();
+        assert_eq!(hive.evaluations(), initial + 9);
+        assert_eq!(hive.evaluations(), hive.context().calls.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn failures_are_tolerated() {
+        for &failure in &[FailurePolicy::Worst, FailurePolicy::Rescout] {
+            let hive = HiveBuilder::new(Simulator::default(), 5)
+                           .set_threads(1)
+                           .set_seed(1)
+                           .set_failure_policy(failure)
+                           .build()
+                           .unwrap();
+            let best = hive.run_for_rounds(100).unwrap();
+            assert!(best.solution <= 110);
+            assert!(best.fitness >= 90.0);
+        }
+    }
+
+    // Records the size of each batch it evaluates.
+    #[derive(Default)]
+    struct Batched {
+        batches: Mutex<Vec<usize>>,
+    }
+
+    impl Context for Batched {
+        type Solution = i32;
+
+        walk_moves!();
+
+        fn evaluate_fitness(&self, solution: &i32) -> f64 {
+            *solution as f64
+        }
+
+        fn try_evaluate_batch(&self, solutions: &[i32]) -> ContextResult<Vec<f64>> {
+            self.batches.lock().unwrap().push(solutions.len());
+            Ok(solutions.iter().map(|solution| *solution as f64).collect())
+        }
+    }
+
+    fn synchronous(threads: usize) -> Hive<Batched> {
+        HiveBuilder::new(Batched::default(), 5)
+            .set_observers(3)
+            .set_retries(100)
+            .set_threads(threads)
+            .set_seed(9)
+            .set_mode(Mode::Synchronous)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn synchronous_phases_are_batched() {
+        let hive = synchronous(1);
+        hive.run_for_rounds(4).unwrap();
+        assert_eq!(*hive.context().batches.lock().unwrap(),
+                   vec![5, 3, 5, 3, 5, 3, 5, 3]);
+        assert_eq!(hive.statistics().rounds, 4);
+        assert_eq!(hive.evaluations(), 5 + 4 * 8);
+
+        hive.run_for_evaluations(7).unwrap();
+        assert_eq!(hive.context().batches.lock().unwrap()[8..], [5, 2]);
+        assert_eq!(hive.evaluations(), 5 + 4 * 8 + 7);
+    }
+
+    #[test]
+    fn batches_default_to_fallible_evaluations() {
+        let hive = HiveBuilder::new(Simulator::default(), 5)
+                       .set_threads(1)
+                       .set_seed(1)
+                       .set_failure_policy(FailurePolicy::Retry(1))
+                       .set_mode(Mode::Synchronous)
+                       .build()
+                       .unwrap();
+        // Every batch fails on its second solution, and again on retry.
+        match hive.run_for_rounds(10) {
+            Err(AbcError::User(error)) => assert_eq!(error.to_string(), "flaky"),
+            other => panic!("Expected a user error, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn synchronous_runs_ignore_threads() {
+        let solutions = |hive: Hive<Batched>| {
+            hive.run_for_rounds(10).unwrap();
+            hive.current_working().unwrap().iter().map(|c| c.solution).collect::<Vec<_>>()
+        };
+        assert_eq!(solutions(synchronous(1)), solutions(synchronous(4)));
+    }
+
+    #[test]
+    fn synchronous_batches_are_split_between_threads() {
+        let hive = synchronous(4);
+        hive.run_for_rounds(1).unwrap();
+        let batches = hive.context().batches.lock().unwrap();
+        assert_eq!(batches.iter().sum::<usize>(), 8);
+        assert!(batches.iter().all(|size| *size <= 2));
+    }
+
+    // Runs a hive for 20 rounds, counting the candidates abandoned in each.
+    fn abandonments(builder: HiveBuilder<Walk>) -> Vec<usize> {
+        use std::sync::Arc;
+
+        #[derive(Default)]
+        struct Abandonments {
+            current: AtomicUsize,
+            rounds: Mutex<Vec<usize>>,
+        }
+
+        struct Counter(Arc<Abandonments>);
+
+        impl HiveObserver<i32> for Counter {
+            fn round_completed(&self, _: usize) {
+                let count = self.0.current.swap(0, Ordering::SeqCst);
+                self.0.rounds.lock().unwrap().push(count);
+            }
+
+            fn candidate_abandoned(&self, _: usize, _: &Candidate<i32>, _: &Candidate<i32>) {
+                self.0.current.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let abandonments = Arc::new(Abandonments::default());
+        let hive = builder.add_hive_observer(Box::new(Counter(abandonments.clone())))
+                          .build()
+                          .unwrap();
+        hive.run_for_rounds(20).unwrap();
+
+        let rounds = abandonments.rounds.lock().unwrap().clone();
+        assert_eq!(rounds.len(), 20);
+        rounds
+    }
+
+    #[test]
+    fn synchronous_scouts_once_per_round() {
+        let builder = HiveBuilder::new(Walk, 10)
+                          .set_retries(1)
+                          .set_seed(3)
+                          .set_mode(Mode::Synchronous);
+        let rounds = abandonments(builder);
+        assert!(rounds.iter().all(|count| *count <= 1));
+        assert!(rounds.iter().any(|count| *count == 1));
+    }
+
+    #[test]
+    fn scout_policies() {
+        let builder = || {
+            HiveBuilder::new(Walk, 10)
+                .set_retries(1)
+                .set_threads(1)
+                .set_seed(3)
+        };
+
+        let one = abandonments(builder().set_scout_policy(ScoutPolicy::OnePerCycle));
+        assert!(one.iter().all(|count| *count <= 1));
+
+        let all = abandonments(builder().set_scout_policy(ScoutPolicy::AllExhaustedPerCycle));
+        assert!(all.iter().any(|count| *count > 1));
+
+        let immediate = abandonments(builder().set_mode(Mode::Synchronous)
+                                              .set_scout_policy(ScoutPolicy::Immediate));
+        assert!(immediate.iter().any(|count| *count > 1));
+    }
+
+    #[test]
+    fn retries_default_to_limit() {
+        use real::RealVectorContext;
+        let context = RealVectorContext::new(vec![(0.0, 1.0); 3], |x: &[f64]| x[0]);
+        let hive = HiveBuilder::new(context, 10).build().unwrap();
+        assert!(hive.snapshot().unwrap().retries.iter().all(|retries| *retries == 30));
+        assert_eq!(HiveBuilder::new(Walk, 10).build().unwrap().snapshot().unwrap().retries[0],
+                   10);
+    }
+
+    fn sphere() -> ::real::RealVectorContext {
+        ::real::RealVectorContext::new(vec![(-5.0, 5.0); 4],
+                                       |x: &[f64]| x.iter().map(|xi| xi * xi).sum())
+    }
+
+    #[test]
+    fn initializers() {
+        for &initializer in &[Initializer::Random,
+                              Initializer::Opposition,
+                              Initializer::LatinHypercube,
+                              Initializer::Halton] {
+            let hive = HiveBuilder::new(sphere(), 8)
+                           .set_threads(2)
+                           .set_initializer(initializer)
+                           .build()
+                           .unwrap();
+            let field = hive.current_working().unwrap();
+            assert_eq!(field.len(), 8);
+            assert!(field.iter().all(|c| c.solution.iter().all(|x| *x >= -5.0 && *x <= 5.0)));
+            let evaluations = if initializer == Initializer::Opposition { 16 } else { 8 };
+            assert_eq!(hive.evaluations(), evaluations);
+        }
+    }
+
+    #[test]
+    fn opposition_keeps_the_fittest() {
+        let hive = HiveBuilder::new(sphere(), 8)
+                       .set_threads(1)
+                       .set_seed(2)
+                       .set_initializer(Initializer::Opposition)
+                       .build()
+                       .unwrap();
+        let worst = hive.current_working()
+                        .unwrap()
+                        .iter()
+                        .map(|c| c.fitness)
+                        .fold(f64::INFINITY, f64::min);
+
+        // Every discarded solution is the opposite of one that was kept, and
+        // is no fitter than the least fit that was kept.
+        let field = hive.current_working().unwrap();
+        for candidate in &field {
+            let opposite = hive.context().opposite(&candidate.solution).unwrap();
+            let kept = field.iter().any(|c| c.solution == opposite);
+            let fitness = ::objective::Objective::Minimize.fitness(hive.context().cost(&opposite));
+            assert!(kept || fitness <= worst);
+        }
+    }
+
+    #[test]
+    fn known_solutions_are_kept() {
+        let hive = HiveBuilder::new(sphere(), 4)
+                       .set_initializer(Initializer::Opposition)
+                       .add_known_solution(vec![0.0; 4])
+                       .add_known_solution(vec![5.0; 4])
+                       .build()
+                       .unwrap();
+        let field = hive.current_working().unwrap();
+        assert!(field.iter().any(|c| c.solution == vec![5.0; 4]));
+        assert_eq!(hive.get().unwrap().solution, vec![0.0; 4]);
+        assert_eq!(hive.evaluations(), 2 + 4);
+    }
+
+    #[test]
+    fn unsupported_initializer() {
+        match HiveBuilder::new(Walk, 4).set_initializer(Initializer::Halton).build() {
+            Err(AbcError::InvalidConfiguration(_)) => {}
+            other => panic!("Expected an invalid configuration, got {:?}", other.is_ok()),
+        }
+        match HiveBuilder::new(Walk, 1).add_known_solution(1).add_known_solution(2).build() {
+            Err(AbcError::InvalidConfiguration(_)) => {}
+            other => panic!("Expected an invalid configuration, got {:?}", other.is_ok()),
+        }
+    }
+
+    /// Schaffer's first problem: the trade-offs lie between 0 and 2.
+    struct Schaffer;
+
+    impl Context for Schaffer {
+        type Solution = f64;
+
+        thread_rng_moves!(f64);
+
+        fn make_with_rng(&self, rng: &mut HiveRng) -> f64 {
+            rng.gen_range(-10.0, 10.0)
+        }
+
+        fn evaluate_fitness(&self, solution: &f64) -> f64 {
+            self.direction().fitness(solution * solution)
+        }
+
+        fn try_evaluate_objectives(&self, solution: &f64) -> ContextResult<Vec<f64>> {
+            Ok(vec![solution * solution, (solution - 2.0) * (solution - 2.0)])
+        }
+
+        fn direction(&self) -> ::objective::Objective {
+            ::objective::Objective::Minimize
+        }
+
+        fn explore_with_rng(&self, field: &[Candidate<f64>], n: usize, rng: &mut HiveRng) -> f64 {
+            field[n].solution + rng.gen_range(-0.5, 0.5)
+        }
+    }
+
+    #[test]
+    fn pareto_archive_finds_the_front() {
+        let hive = HiveBuilder::new(Schaffer, 10)
+                       .set_threads(1)
+                       .set_seed(7)
+                       .set_pareto_archive(8)
+                       .build()
+                       .unwrap();
+        hive.run_for_rounds(100).unwrap();
+
+        let archive = hive.archive().unwrap();
+        assert_eq!(archive.len(), 8);
+        for member in &archive {
+            assert!(member.solution >= -0.1 && member.solution <= 2.1);
+            assert_eq!(member.objective, member.objectives[0]);
+            assert!(!archive.iter().any(|other| {
+                ::pareto::dominates(&other.objectives, &member.objectives, Schaffer.direction())
+            }));
+        }
+    }
+
+    #[test]
+    fn pareto_archive_streams() {
+        let receiver = HiveBuilder::new(Schaffer, 5)
+                           .set_threads(1)
+                           .set_pareto_archive(4)
+                           .build()
+                           .unwrap()
+                           .stream();
+        for member in receiver.iter().take(10) {
+            assert!(member.is_feasible());
+            assert_eq!(member.objectives.len(), 2);
+        }
+    }
+
+    #[test]
+    fn snapshots_keep_the_archive() {
+        let hive = HiveBuilder::new(Schaffer, 5)
+                       .set_threads(1)
+                       .set_seed(3)
+                       .set_pareto_archive(4)
+                       .build()
+                       .unwrap();
+        hive.run_for_rounds(20).unwrap();
+        let snapshot = hive.snapshot().unwrap();
+        assert_eq!(snapshot.archive.len(), hive.archive().unwrap().len());
+
+        let resumed = HiveBuilder::new(Schaffer, 1)
+                          .set_pareto_archive(4)
+                          .resume_from(snapshot.clone())
+                          .build()
+                          .unwrap();
+        let solutions = |archive: Vec<Candidate<f64>>| {
+            let mut solutions = archive.iter().map(|c| c.solution).collect::<Vec<f64>>();
+            solutions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            solutions
+        };
+        assert_eq!(solutions(resumed.archive().unwrap()), solutions(snapshot.archive));
+    }
+
+    /// Walks up towards a ceiling of 50.
+    struct Ceiling;
+
+    impl Context for Ceiling {
+        type Solution = i32;
+
+        walk_moves!();
+
+        fn evaluate_fitness(&self, solution: &i32) -> f64 {
+            *solution as f64
+        }
+
+        fn constraint_violation(&self, solution: &i32) -> f64 {
+            (*solution - 50) as f64
+        }
+    }
+
+    #[test]
+    fn feasible_candidates_win() {
+        let hive = HiveBuilder::new(Ceiling, 5).set_threads(1).set_seed(3).build().unwrap();
+        let best = hive.run_for_rounds(50).unwrap();
+        assert!(best.is_feasible());
+        assert!(best.solution > 40 && best.solution <= 50);
+        for candidate in hive.current_working().unwrap() {
+            assert_eq!(candidate.violation, (candidate.solution - 50).max(0) as f64);
+        }
+    }
+
+    #[test]
+    fn infeasible_moves_can_be_accepted() {
+        let hive = HiveBuilder::new(Ceiling, 5)
+                       .set_threads(1)
+                       .set_seed(3)
+                       .set_infeasible_acceptance(1.0)
+                       .build()
+                       .unwrap();
+        let best = hive.run_for_rounds(50).unwrap();
+        assert!(best.is_feasible());
+        assert!(hive.current_working().unwrap().iter().any(|c| c.solution > 100));
+    }
+
+    /// Wanders among a handful of solutions, counting its evaluations.
+    struct Pen(AtomicUsize);
+
+    impl Context for Pen {
+        type Solution = i32;
+
+        thread_rng_moves!(i32);
+
+        fn make_with_rng(&self, rng: &mut HiveRng) -> i32 {
+            rng.gen_range(0, 10)
+        }
+
+        fn evaluate_fitness(&self, solution: &i32) -> f64 {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            *solution as f64
+        }
+
+        fn explore_with_rng(&self, _: &[Candidate<i32>], _: usize, rng: &mut HiveRng) -> i32 {
+            rng.gen_range(0, 10)
+        }
+    }
+
+    #[test]
+    fn cache_spares_evaluations() {
+        let hive = HiveBuilder::new(Pen(AtomicUsize::new(0)), 5)
+                       .set_threads(2)
+                       .set_evaluation_cache(20)
+                       .build()
+                       .unwrap();
+        hive.run_for_rounds(20).unwrap();
+
+        let statistics = hive.statistics();
+        assert_eq!(statistics.evaluations, statistics.cache_misses);
+        assert_eq!(statistics.cache_misses, hive.context().0.load(Ordering::SeqCst));
+        assert!(statistics.cache_hits > statistics.cache_misses);
+    }
+
+    #[test]
+    fn cache_hits_leave_the_budget_alone() {
+        let hive = HiveBuilder::new(Pen(AtomicUsize::new(0)), 5)
+                       .set_threads(2)
+                       .set_evaluation_cache(5)
+                       .build()
+                       .unwrap();
+        let initial = hive.evaluations();
+        hive.run_for_evaluations(30).unwrap();
+
+        let statistics = hive.statistics();
+        assert_eq!(statistics.evaluations, initial + 30);
+        assert_eq!(statistics.evaluations, statistics.cache_misses);
+        assert_eq!(statistics.cache_misses, hive.context().0.load(Ordering::SeqCst));
+        assert!(statistics.cache_hits > 0);
+    }
+
+    #[test]
+    fn cache_forgets_beyond_capacity() {
+        let hive = HiveBuilder::new(Pen(AtomicUsize::new(0)), 5)
+                       .set_threads(1)
+                       .set_evaluation_cache(1)
+                       .build()
+                       .unwrap();
+        hive.run_for_rounds(20).unwrap();
+        let statistics = hive.statistics();
+        assert!(statistics.cache_misses > 10);
+        assert_eq!(statistics.cache_misses, hive.context().0.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn no_cache_no_statistics() {
+        let hive = HiveBuilder::new(Walk, 5).set_threads(1).build().unwrap();
+        hive.run_for_rounds(5).unwrap();
+        assert_eq!(hive.statistics().cache_hits, 0);
+        assert_eq!(hive.statistics().cache_misses, 0);
+    }
+}
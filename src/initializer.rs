@@ -0,0 +1,134 @@
+extern crate rand;
+
+use self::rand::Rng;
+
+use context::HiveRng;
+
+/// How a hive fills its field with its first candidates.
+///
+/// Set the strategy with
+/// [`HiveBuilder::set_initializer`](struct.HiveBuilder.html#method.set_initializer).
+/// Known good solutions can also be placed in the field with
+/// [`HiveBuilder::add_known_solution`](struct.HiveBuilder.html#method.add_known_solution),
+/// in which case the strategy only fills the remaining places. Scouts always
+/// make their new solutions at random.
+///
+/// Apart from `Random`, the strategies rely on optional methods of the
+/// [`Context`](trait.Context.html), which the
+/// [`RealVectorContext`](real/struct.RealVectorContext.html) implements. If
+/// the context does not support the chosen strategy,
+/// [`HiveBuilder::build`](struct.HiveBuilder.html#method.build) fails with
+/// [`Error::InvalidConfiguration`](enum.Error.html#variant.InvalidConfiguration).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Initializer {
+    /// Make each solution with
+    /// [`Context::make_with_rng`](trait.Context.html#method.make_with_rng).
+    ///
+    /// This is the default.
+    Random,
+
+    /// Opposition-based initialization, after Rahnamayan et al.
+    ///
+    /// Each random solution is paired with its
+    /// [`opposite`](trait.Context.html#method.opposite), and the fittest
+    /// half of all of these solutions make up the field. This takes twice as
+    /// many evaluations as random initialization.
+    Opposition,
+
+    /// Latin hypercube sampling.
+    ///
+    /// The range of each dimension is split into as many equal strata as
+    /// there are solutions to make, and each stratum is used exactly once.
+    /// Requires [`dimensions`](trait.Context.html#method.dimensions) and
+    /// [`solution_at`](trait.Context.html#method.solution_at).
+    LatinHypercube,
+
+    /// Points from a Halton low-discrepancy sequence, randomly shifted.
+    ///
+    /// The points cover the space more evenly than random ones, which helps
+    /// most when the field is small relative to the number of dimensions.
+    /// Requires [`dimensions`](trait.Context.html#method.dimensions) and
+    /// [`solution_at`](trait.Context.html#method.solution_at).
+    Halton,
+}
+
+/// Draws `count` points from a Latin hypercube in `dimensions` dimensions.
+pub fn latin_hypercube(count: usize, dimensions: usize, rng: &mut HiveRng) -> Vec<Vec<f64>> {
+    let mut points = vec![Vec::with_capacity(dimensions); count];
+    for _ in 0..dimensions {
+        let mut strata = (0..count).collect::<Vec<usize>>();
+        rng.shuffle(&mut strata);
+        for (point, stratum) in points.iter_mut().zip(strata) {
+            point.push((stratum as f64 + rng.next_f64()) / count as f64);
+        }
+    }
+    points
+}
+
+/// Takes `count` points from a Halton sequence in `dimensions` dimensions.
+///
+/// Each dimension is shifted by a random amount, modulo 1, so that
+/// different generators give different, but equally even, points.
+pub fn halton(count: usize, dimensions: usize, rng: &mut HiveRng) -> Vec<Vec<f64>> {
+    let shifts = primes(dimensions)
+                     .into_iter()
+                     .map(|base| (base, rng.next_f64()))
+                     .collect::<Vec<_>>();
+    (1..count + 1)
+        .map(|index| {
+            shifts.iter()
+                  .map(|&(base, shift)| (radical_inverse(index, base) + shift) % 1.0)
+                  .collect()
+        })
+        .collect()
+}
+
+/// Reflects the digits of `index` in `base` about the radix point.
+fn radical_inverse(mut index: usize, base: usize) -> f64 {
+    let mut inverse = 0_f64;
+    let mut scale = 1_f64 / base as f64;
+    while index > 0 {
+        inverse += (index % base) as f64 * scale;
+        index /= base;
+        scale /= base as f64;
+    }
+    inverse
+}
+
+/// Finds the first `count` primes.
+fn primes(count: usize) -> Vec<usize> {
+    let mut primes = Vec::with_capacity(count);
+    let mut candidate = 2;
+    while primes.len() < count {
+        if primes.iter().all(|prime| candidate % prime != 0) {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use self::rand::SeedableRng;
+
+    #[test]
+    fn halton_sequence() {
+        assert_eq!(primes(5), vec![2, 3, 5, 7, 11]);
+        let inverses = (1..5).map(|i| radical_inverse(i, 2)).collect::<Vec<_>>();
+        assert_eq!(inverses, vec![0.5, 0.25, 0.75, 0.125]);
+        assert!((radical_inverse(5, 3) - 7.0 / 9.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn latin_hypercube_uses_each_stratum() {
+        let mut rng = HiveRng::from_seed([1, 2, 3, 4]);
+        let points = latin_hypercube(8, 3, &mut rng);
+        for d in 0..3 {
+            let mut strata = points.iter().map(|p| (p[d] * 8.0) as usize).collect::<Vec<_>>();
+            strata.sort();
+            assert_eq!(strata, (0..8).collect::<Vec<_>>());
+        }
+    }
+}
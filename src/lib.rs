@@ -22,7 +22,7 @@
 //!
 //! use std::f32::consts::PI;
 //! use rand::{random, Closed01, thread_rng, Rng};
-//! use abc::{Context, Candidate, HiveBuilder};
+//! use abc::{Context, Candidate, HiveBuilder, Objective};
 //!
 //! const SIZE: usize = 10;
 //!
@@ -51,17 +51,19 @@
 //!     }
 //!
 //!     fn evaluate_fitness(&self, solution: &[f32;10]) -> f64 {
+//!         self.direction().fitness(self.evaluate_objective(solution))
+//!     }
+//!
+//!     fn evaluate_objective(&self, solution: &[f32;10]) -> f64 {
 //!         let sum = solution.iter()
 //!                           .map(|x| x.powf(2.0) - self.a * (*x * 2.0 * PI).cos())
 //!                           .fold(0.0, |total, next| total + next);
-//!         let rastrigin = ((self.a * SIZE as f32) + sum) as f64;
+//!         ((self.a * SIZE as f32) + sum) as f64
+//!     }
 //!
-//!         // Minimize.
-//!         if rastrigin >= 0.0 {
-//!             1.0 / (1.0 + rastrigin)
-//!         } else {
-//!             1.0 + rastrigin.abs()
-//!         }
+//!     // Minimize.
+//!     fn direction(&self) -> Objective {
+//!         Objective::Minimize
 //!     }
 //!
 //!     fn explore(&self, field: &[Candidate<[f32;SIZE]>], index: usize) -> [f32;SIZE] {
@@ -75,7 +77,7 @@
 //!         for i in 0..SIZE {
 //!             // Choose a different vector at random.
 //!             let mut rng = thread_rng();
-//!             let mut index2 = rng.gen_range(0, current.len() - 1);
+//!             let mut index2 = rng.gen_range(0, field.len() - 1);
 //!             if index2 >= index { index2 += 1; }
 //!             let ref other = field[index2].solution;
 //!
@@ -121,15 +123,56 @@
 //! }
 //! ```
 
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+#[cfg(all(test, feature = "async"))]
+extern crate tokio;
+
+#[cfg(test)]
+#[macro_use]
+mod testing;
 mod result;
 mod task;
 mod context;
 mod candidate;
+mod objective;
+mod failure;
+mod mode;
+mod scout;
+mod initializer;
+mod pareto;
+mod cache;
 mod hive;
+mod archipelago;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod statistics;
+mod observer;
+mod snapshot;
 
 pub mod scaling;
+pub mod selection;
+pub mod stop;
+pub mod real;
+pub mod binary;
+pub mod permutation;
+pub mod space;
 
 pub use result::{Error, Result};
-pub use context::Context;
+pub use context::{Context, ContextResult, HiveRng, Exploration};
 pub use candidate::Candidate;
+pub use failure::FailurePolicy;
+pub use mode::Mode;
+pub use scout::ScoutPolicy;
+pub use initializer::Initializer;
+pub use objective::Objective;
 pub use hive::{HiveBuilder, Hive};
+pub use archipelago::{Archipelago, Topology};
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncContext, AsyncHiveBuilder, AsyncHive, Build, Run, Evaluation};
+pub use statistics::Statistics;
+pub use observer::{HiveObserver, Bee};
+pub use snapshot::Snapshot;
@@ -0,0 +1,33 @@
+/// How a hive schedules its bees' work.
+///
+/// Set the mode with
+/// [`HiveBuilder::set_mode`](struct.HiveBuilder.html#method.set_mode).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Bees work independently, on as many threads as the hive has.
+    ///
+    /// Each bee looks at the field as it is when it starts work, so the
+    /// rounds are staggered: some observers may still be at work when the
+    /// next round's workers start. This keeps every thread busy, and is the
+    /// default.
+    Asynchronous,
+
+    /// Bees work in generations, exploring on a single thread.
+    ///
+    /// Each generation follows the phases of the published algorithm. All of
+    /// the workers explore the same field, and their variants are evaluated
+    /// together with one call to
+    /// [`Context::try_evaluate_batch`](trait.Context.html#method.try_evaluate_batch)
+    /// per thread: the variants are split evenly between the hive's threads,
+    /// so a hive with a single thread evaluates each phase as one batch. The
+    /// split does not change the results, so seeded runs repeat whatever the
+    /// number of threads. The observers' probabilities are then computed
+    /// once, from the updated field, and their variants are evaluated in a
+    /// second batch. Finally, a single scout phase abandons exhausted
+    /// candidates, by default only the most exhausted of them, as described
+    /// for [`ScoutPolicy`](enum.ScoutPolicy.html). Results are directly
+    /// comparable with published ones, and the batches suit objectives that
+    /// are much cheaper to evaluate in bulk, such as those that run on a GPU
+    /// or a remote server.
+    Synchronous,
+}
@@ -0,0 +1,46 @@
+/// Direction in which a context's objective should be optimized.
+///
+/// The hive always maximizes fitness. A context that reports a raw objective
+/// through [`Context::evaluate_objective`](trait.Context.html#method.evaluate_objective)
+/// also reports the direction of that objective, and the hive derives the
+/// fitness from the two.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+    /// Lower objective values are better.
+    ///
+    /// Objective values are converted to fitness with the standard
+    /// transformation used with ABC:
+    ///
+    /// fitness = 1 / (1 + objective) if objective ≥ 0, or
+    /// 1 + |objective| otherwise.
+    Minimize,
+
+    /// Higher objective values are better.
+    ///
+    /// The objective value is used as the fitness, as is.
+    Maximize,
+}
+
+impl Objective {
+    /// Converts an objective value into a fitness, to be maximized.
+    pub fn fitness(&self, objective: f64) -> f64 {
+        match *self {
+            Objective::Minimize if objective >= 0.0 => 1.0 / (1.0 + objective),
+            Objective::Minimize => 1.0 + objective.abs(),
+            Objective::Maximize => objective,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fitness() {
+        assert_eq!(Objective::Minimize.fitness(0.0), 1.0);
+        assert_eq!(Objective::Minimize.fitness(1.0), 0.5);
+        assert_eq!(Objective::Minimize.fitness(-1.0), 2.0);
+        assert_eq!(Objective::Maximize.fitness(-1.0), -1.0);
+    }
+}
@@ -0,0 +1,77 @@
+use candidate::Candidate;
+use statistics::Statistics;
+
+/// Kind of bee that did a piece of work.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bee {
+    /// A bee dedicated to a single candidate.
+    Worker,
+
+    /// A bee that chose a candidate to work on at random.
+    Observer,
+}
+
+/// Receives events from a running hive.
+///
+/// Register a `HiveObserver` with
+/// [`HiveBuilder::add_hive_observer`](struct.HiveBuilder.html#method.add_hive_observer)
+/// to follow a run as it happens, for example to plot convergence or count
+/// how often candidates are abandoned. Every method has an empty default
+/// implementation, so implementors only need to handle the events that
+/// interest them.
+///
+/// Events are delivered from the hive's worker threads, often while the hive
+/// holds a lock on the affected candidate, so the methods should return
+/// quickly. If an observer needs to do something expensive, it should pass
+/// the event along to another thread.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate abc; fn main() {
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use abc::{Candidate, HiveObserver};
+///
+/// #[derive(Default)]
+/// struct Abandonments(AtomicUsize);
+///
+/// impl HiveObserver<i32> for Abandonments {
+///     fn candidate_abandoned(&self, _: usize, _: &Candidate<i32>, _: &Candidate<i32>) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+/// # }
+/// ```
+pub trait HiveObserver<S: Clone + Send + Sync + 'static>: Send + Sync {
+    /// Called when every task in `round` has been claimed by a worker thread.
+    ///
+    /// Since the algorithm staggers the rounds, some of the round's work may
+    /// still be in progress.
+    fn round_completed(&self, round: usize) {
+        let _ = round;
+    }
+
+    /// Called when a bee finds an improvement on the candidate at `index`.
+    fn candidate_improved(&self, index: usize, bee: Bee, candidate: &Candidate<S>) {
+        let _ = (index, bee, candidate);
+    }
+
+    /// Called when the candidate at `index` has gone unimproved too many
+    /// times, and has been replaced by a scout.
+    fn candidate_abandoned(&self,
+                           index: usize,
+                           abandoned: &Candidate<S>,
+                           scouted: &Candidate<S>) {
+        let _ = (index, abandoned, scouted);
+    }
+
+    /// Called when the hive finds a new best candidate.
+    fn new_best(&self, candidate: &Candidate<S>) {
+        let _ = candidate;
+    }
+
+    /// Called when a run stops without error.
+    fn run_finished(&self, best: &Candidate<S>, statistics: &Statistics) {
+        let _ = (best, statistics);
+    }
+}
@@ -0,0 +1,211 @@
+use std::f64;
+
+use candidate::Candidate;
+use objective::Objective;
+
+/// Checks whether a set of objective values can be compared with others.
+///
+/// Candidates that could not be evaluated have no values, and those with NaN
+/// values can neither dominate nor be dominated.
+pub fn comparable(objectives: &[f64]) -> bool {
+    !objectives.is_empty() && !objectives.iter().any(|value| value.is_nan())
+}
+
+/// Checks whether `a` is at least as good as `b` in every objective, and
+/// better in at least one.
+pub fn dominates(a: &[f64], b: &[f64], direction: Objective) -> bool {
+    let better = |x: f64, y: f64| {
+        match direction {
+            Objective::Minimize => x < y,
+            Objective::Maximize => x > y,
+        }
+    };
+    let mut strictly = false;
+    for (&x, &y) in a.iter().zip(b) {
+        if better(y, x) {
+            return false;
+        }
+        strictly = strictly || better(x, y);
+    }
+    strictly
+}
+
+/// Sorts points into successive non-dominated fronts.
+///
+/// Returns the front of each point: 0 for the points that no other point
+/// dominates, 1 for those dominated only by points in front 0, and so on.
+pub fn ranks(points: &[&[f64]], direction: Objective) -> Vec<usize> {
+    let mut ranks = vec![usize::MAX; points.len()];
+    let mut remaining = (0..points.len()).collect::<Vec<usize>>();
+    let mut rank = 0;
+    while !remaining.is_empty() {
+        let front = remaining.iter()
+                             .cloned()
+                             .filter(|&i| {
+                                 !remaining.iter().any(|&j| dominates(points[j], points[i], direction))
+                             })
+                             .collect::<Vec<usize>>();
+        for &i in &front {
+            ranks[i] = rank;
+        }
+        remaining.retain(|i| !front.contains(i));
+        rank += 1;
+    }
+    ranks
+}
+
+/// Turns the candidates' Pareto ranks into fitnesses, for onlookers to
+/// choose by.
+///
+/// Candidates in front *r* get a fitness of 1 / (1 + *r*). Candidates that
+/// cannot be compared get the worst possible fitness.
+pub fn rank_fitnesses<S>(candidates: &[Candidate<S>], direction: Objective) -> Vec<f64>
+    where S: Clone + Send + Sync + 'static
+{
+    let (indices, points): (Vec<usize>, Vec<&[f64]>) =
+        candidates.iter()
+                  .enumerate()
+                  .filter(|&(_, candidate)| comparable(&candidate.objectives))
+                  .map(|(i, candidate)| (i, &candidate.objectives[..]))
+                  .unzip();
+
+    let mut fitnesses = vec![f64::NEG_INFINITY; candidates.len()];
+    for (i, rank) in indices.into_iter().zip(ranks(&points, direction)) {
+        fitnesses[i] = 1.0 / (1.0 + rank as f64);
+    }
+    fitnesses
+}
+
+/// Measures how crowded each point is by its neighbours, after Deb et al.
+///
+/// For each objective, a point's neighbours on either side contribute the
+/// distance between them, relative to the range of that objective. The
+/// points at the ends of each range are infinitely far from the rest, so
+/// that they are always kept.
+pub fn crowding_distances(points: &[&[f64]]) -> Vec<f64> {
+    let mut distances = vec![0_f64; points.len()];
+    if points.len() < 3 {
+        return vec![f64::INFINITY; points.len()];
+    }
+
+    for m in 0..points[0].len() {
+        let mut order = (0..points.len()).collect::<Vec<usize>>();
+        order.sort_by(|&a, &b| {
+            points[a][m].partial_cmp(&points[b][m]).unwrap_or(::std::cmp::Ordering::Equal)
+        });
+        let (first, last) = (order[0], order[order.len() - 1]);
+        distances[first] = f64::INFINITY;
+        distances[last] = f64::INFINITY;
+
+        let range = points[last][m] - points[first][m];
+        if range > 0.0 {
+            for k in 1..order.len() - 1 {
+                let gap = points[order[k + 1]][m] - points[order[k - 1]][m];
+                distances[order[k]] += gap / range;
+            }
+        }
+    }
+    distances
+}
+
+/// Offers a candidate to an archive of non-dominated candidates.
+///
+/// The candidate is turned away if a member of the archive dominates it, or
+/// has the same objective values. Otherwise, it joins the archive, and any
+/// members that it dominates are removed. If the archive then holds more
+/// than `capacity` candidates, the most crowded are removed.
+///
+/// Returns `true` if the archive changed.
+pub fn insert<S>(archive: &mut Vec<Candidate<S>>,
+                 candidate: &Candidate<S>,
+                 capacity: usize,
+                 direction: Objective)
+                 -> bool
+    where S: Clone + Send + Sync + 'static
+{
+    if !comparable(&candidate.objectives) {
+        return false;
+    }
+    let rejected = archive.iter().any(|member| {
+        member.objectives == candidate.objectives ||
+        dominates(&member.objectives, &candidate.objectives, direction)
+    });
+    if rejected {
+        return false;
+    }
+
+    archive.retain(|member| !dominates(&candidate.objectives, &member.objectives, direction));
+    archive.push(candidate.clone());
+    while archive.len() > capacity {
+        let most_crowded = {
+            let points = archive.iter().map(|member| &member.objectives[..]).collect::<Vec<_>>();
+            let distances = crowding_distances(&points);
+            (0..distances.len()).fold(0, |most, i| {
+                if distances[i] < distances[most] {
+                    i
+                } else {
+                    most
+                }
+            })
+        };
+        archive.remove(most_crowded);
+    }
+    // Pruning only happens if the candidate dominated no members, in which
+    // case the archive is unchanged if the candidate was the most crowded.
+    archive.iter().any(|member| member.objectives == candidate.objectives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(objectives: Vec<f64>) -> Candidate<()> {
+        let mut candidate = Candidate::new((), 0.0);
+        candidate.objectives = objectives;
+        candidate
+    }
+
+    #[test]
+    fn dominance() {
+        let min = Objective::Minimize;
+        assert!(dominates(&[1.0, 2.0], &[1.0, 3.0], min));
+        assert!(!dominates(&[1.0, 2.0], &[1.0, 2.0], min));
+        assert!(!dominates(&[1.0, 3.0], &[2.0, 2.0], min));
+        assert!(dominates(&[1.0, 3.0], &[1.0, 2.0], Objective::Maximize));
+    }
+
+    #[test]
+    fn fronts() {
+        let points: Vec<&[f64]> = vec![&[1.0, 4.0], &[2.0, 2.0], &[3.0, 3.0], &[4.0, 1.0], &[4.0, 4.0]];
+        assert_eq!(ranks(&points, Objective::Minimize), vec![0, 0, 1, 0, 2]);
+    }
+
+    #[test]
+    fn crowding_keeps_extremes() {
+        let points: Vec<&[f64]> = vec![&[0.0, 4.0], &[1.0, 3.0], &[3.5, 0.5], &[4.0, 0.0]];
+        let distances = crowding_distances(&points);
+        assert_eq!(distances[0], f64::INFINITY);
+        assert_eq!(distances[3], f64::INFINITY);
+        assert!(distances[2] < distances[1]);
+    }
+
+    #[test]
+    fn archive_is_non_dominated_and_bounded() {
+        let min = Objective::Minimize;
+        let mut archive = Vec::new();
+        assert!(insert(&mut archive, &candidate(vec![2.0, 2.0]), 3, min));
+        assert!(!insert(&mut archive, &candidate(vec![3.0, 3.0]), 3, min));
+        assert!(!insert(&mut archive, &candidate(vec![2.0, 2.0]), 3, min));
+        assert!(!insert(&mut archive, &candidate(vec![f64::NAN, 0.0]), 3, min));
+        assert!(insert(&mut archive, &candidate(vec![0.0, 4.0]), 3, min));
+        assert!(insert(&mut archive, &candidate(vec![4.0, 0.0]), 3, min));
+        assert!(insert(&mut archive, &candidate(vec![1.0, 1.0]), 3, min));
+        assert_eq!(archive.len(), 3);
+
+        // The archive is full, and the new point is the most crowded.
+        assert!(!insert(&mut archive, &candidate(vec![0.9, 1.2]), 3, min));
+        let mut kept = archive.iter().map(|c| c.objectives[0]).collect::<Vec<f64>>();
+        kept.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(kept, vec![0.0, 1.0, 4.0]);
+    }
+}
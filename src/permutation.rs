@@ -0,0 +1,254 @@
+//! Ready-made context for optimizing over permutations.
+//!
+//! Sequencing problems, like job-shop scheduling or the travelling salesman
+//! problem, search for the best ordering of a fixed set of items. A
+//! [`PermutationContext`](struct.PermutationContext.html) wraps a cost
+//! function of such an ordering, written as a `Vec<usize>` holding each of
+//! the indices `0..n` exactly once, and explores it with a selectable
+//! [`Neighbourhood`](enum.Neighbourhood.html).
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate abc; fn main() {
+//! use abc::HiveBuilder;
+//! use abc::permutation::{PermutationContext, Neighbourhood};
+//!
+//! // A small travelling salesman problem, with cities on a line.
+//! let cities = [0.0, 3.0, 1.0, 4.0, 2.0_f64];
+//! let context = PermutationContext::new(cities.len(), move |tour: &[usize]| {
+//!     (0..tour.len())
+//!         .map(|i| (cities[tour[i]] - cities[tour[(i + 1) % tour.len()]]).abs())
+//!         .sum()
+//! }).set_neighbourhood(Neighbourhood::TwoOpt);
+//!
+//! let hive = HiveBuilder::new(context, 10).build().unwrap();
+//! let best = hive.run_for_rounds(50).unwrap();
+//! println!("{:?} has length {}", best.solution, best.objective);
+//! # }
+//! ```
+
+extern crate rand;
+
+use self::rand::{thread_rng, Rng};
+
+use candidate::Candidate;
+use context::{Context, HiveRng, random_neighbour};
+use objective::Objective;
+
+/// Computes the objective value of a permutation.
+pub type PermutationFunction = Fn(&[usize]) -> f64 + Send + Sync + 'static;
+
+/// Move used to explore near a permutation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Exchange the items at two random positions.
+    Swap,
+
+    /// Remove the item at one random position, and reinsert it at another.
+    Insertion,
+
+    /// Reverse the order of the items between two random positions.
+    ///
+    /// For tours, this is the classic 2-opt move, which replaces two edges.
+    TwoOpt,
+
+    /// Order crossover (OX) with a randomly chosen neighbour from the field.
+    ///
+    /// A random segment of the permutation is kept in place, and the other
+    /// positions are filled with the remaining items, in the order in which
+    /// they appear in the neighbour, starting after the segment. If the
+    /// result is identical to the original, a swap is applied instead.
+    OrderCrossover,
+}
+
+/// Optimizes a function over permutations of `0..n`.
+///
+/// By default, the objective is minimized with the
+/// [`Swap`](enum.Neighbourhood.html#variant.Swap) neighbourhood.
+pub struct PermutationContext {
+    length: usize,
+    objective: Box<PermutationFunction>,
+    direction: Objective,
+    neighbourhood: Neighbourhood,
+}
+
+impl PermutationContext {
+    /// Creates a context that will minimize `objective`.
+    ///
+    /// * `length` - Number of items in each permutation.
+    /// * `objective` - Objective value of a permutation.
+    pub fn new<F>(length: usize, objective: F) -> PermutationContext
+        where F: Fn(&[usize]) -> f64 + Send + Sync + 'static
+    {
+        if length < 2 {
+            panic!("PermutationContext must have at least two items.");
+        }
+
+        PermutationContext {
+            length: length,
+            objective: Box::new(objective),
+            direction: Objective::Minimize,
+            neighbourhood: Neighbourhood::Swap,
+        }
+    }
+
+    /// Sets whether the objective should be minimized or maximized.
+    ///
+    /// This defaults to `Objective::Minimize`.
+    pub fn set_direction(mut self, direction: Objective) -> PermutationContext {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the neighbourhood used to explore near a permutation.
+    ///
+    /// This defaults to `Neighbourhood::Swap`.
+    pub fn set_neighbourhood(mut self, neighbourhood: Neighbourhood) -> PermutationContext {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    /// Returns the number of items in each permutation.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Chooses two distinct positions, in ascending order.
+    fn positions(&self, rng: &mut HiveRng) -> (usize, usize) {
+        let i = rng.gen_range(0, self.length);
+        let j = random_neighbour(self.length, i, rng);
+        if i < j { (i, j) } else { (j, i) }
+    }
+
+    fn swap(&self, mut permutation: Vec<usize>, rng: &mut HiveRng) -> Vec<usize> {
+        let (i, j) = self.positions(rng);
+        permutation.swap(i, j);
+        permutation
+    }
+}
+
+/// Applies order crossover, keeping `current[start..end]` in place.
+fn order_crossover(current: &[usize], other: &[usize], start: usize, end: usize) -> Vec<usize> {
+    let length = current.len();
+    let mut kept = vec![false; length];
+    for &item in &current[start..end] {
+        kept[item] = true;
+    }
+
+    let mut child = current.to_vec();
+    let mut fill = (end..length).chain(0..start);
+    for offset in 0..length {
+        let item = other[(end + offset) % length];
+        if !kept[item] {
+            if let Some(position) = fill.next() {
+                child[position] = item;
+            }
+        }
+    }
+    child
+}
+
+impl Context for PermutationContext {
+    type Solution = Vec<usize>;
+
+    fn make(&self) -> Vec<usize> {
+        self.make_with_rng(&mut thread_rng().gen())
+    }
+
+    fn make_with_rng(&self, rng: &mut HiveRng) -> Vec<usize> {
+        let mut permutation = (0..self.length).collect::<Vec<_>>();
+        rng.shuffle(&mut permutation);
+        permutation
+    }
+
+    fn evaluate_fitness(&self, solution: &Vec<usize>) -> f64 {
+        self.direction().fitness(self.evaluate_objective(solution))
+    }
+
+    fn evaluate_objective(&self, solution: &Vec<usize>) -> f64 {
+        (self.objective)(solution)
+    }
+
+    fn direction(&self) -> Objective {
+        self.direction
+    }
+
+    fn dimensions(&self) -> Option<usize> {
+        Some(self.length)
+    }
+
+    fn explore(&self, field: &[Candidate<Vec<usize>>], index: usize) -> Vec<usize> {
+        self.explore_with_rng(field, index, &mut thread_rng().gen())
+    }
+
+    fn explore_with_rng(&self,
+                        field: &[Candidate<Vec<usize>>],
+                        index: usize,
+                        rng: &mut HiveRng)
+                        -> Vec<usize> {
+        let current = &field[index].solution;
+        match self.neighbourhood {
+            Neighbourhood::Swap => self.swap(current.clone(), rng),
+            Neighbourhood::Insertion => {
+                let mut variant = current.clone();
+                let from = rng.gen_range(0, self.length);
+                let to = random_neighbour(self.length, from, rng);
+                let item = variant.remove(from);
+                variant.insert(to, item);
+                variant
+            }
+            Neighbourhood::TwoOpt => {
+                let mut variant = current.clone();
+                let (i, j) = self.positions(rng);
+                variant[i..j + 1].reverse();
+                variant
+            }
+            Neighbourhood::OrderCrossover => {
+                let other = &field[random_neighbour(field.len(), index, rng)].solution;
+                let (start, end) = self.positions(rng);
+                let variant = order_crossover(current, other, start, end);
+                if variant == *current {
+                    self.swap(variant, rng)
+                } else {
+                    variant
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hive::HiveBuilder;
+
+    #[test]
+    fn crossover_keeps_segment() {
+        let current = [0, 1, 2, 3, 4, 5, 6, 7];
+        let other = [7, 6, 5, 4, 3, 2, 1, 0];
+        assert_eq!(order_crossover(&current, &other, 2, 5),
+                   vec![6, 5, 2, 3, 4, 1, 0, 7]);
+    }
+
+    #[test]
+    fn neighbourhoods_keep_permutations() {
+        for &neighbourhood in &[Neighbourhood::Swap,
+                                Neighbourhood::Insertion,
+                                Neighbourhood::TwoOpt,
+                                Neighbourhood::OrderCrossover] {
+            // Minimize the number of items out of place.
+            let context = PermutationContext::new(8, |p: &[usize]| {
+                              p.iter().enumerate().filter(|&(i, x)| i != *x).count() as f64
+                          })
+                              .set_neighbourhood(neighbourhood);
+            let hive = HiveBuilder::new(context, 10).set_threads(1).set_seed(5).build().unwrap();
+            let best = hive.run_for_rounds(100).unwrap();
+
+            let mut sorted = best.solution.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..8).collect::<Vec<_>>());
+            assert!(best.objective <= 4.0);
+        }
+    }
+}
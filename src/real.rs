@@ -0,0 +1,314 @@
+//! Ready-made context for optimizing over real-valued vectors.
+//!
+//! Most continuous problems can be phrased as minimizing some function of a
+//! vector, with each dimension confined to an interval. Rather than write a
+//! [`Context`](../trait.Context.html) for each one, wrap the function in a
+//! [`RealVectorContext`](struct.RealVectorContext.html), which implements the
+//! canonical ABC search over that space.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate abc; fn main() {
+//! use abc::HiveBuilder;
+//! use abc::real::RealVectorContext;
+//!
+//! // Minimize the 5-dimensional sphere function.
+//! let context = RealVectorContext::new(vec![(-5.12, 5.12); 5],
+//!                                      |x: &[f64]| x.iter().map(|xi| xi * xi).sum());
+//! let hive = HiveBuilder::new(context, 20).build().unwrap();
+//! let best = hive.run_for_rounds(100).unwrap();
+//! println!("{:?}", best.solution);
+//! # }
+//! ```
+
+extern crate rand;
+
+use self::rand::{thread_rng, Rng};
+
+use candidate::Candidate;
+use context::{Context, HiveRng, Exploration, random_neighbour};
+use objective::Objective;
+
+/// What to do with a value that has been pushed outside of its bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    /// Move the value to the nearest bound.
+    Clamp,
+
+    /// Mirror the value back into bounds, as though it had bounced off of the
+    /// bound that it crossed. If it would still be out of bounds, clamp it.
+    Reflect,
+}
+
+impl Boundary {
+    fn apply(&self, value: f64, (min, max): (f64, f64)) -> f64 {
+        let value = match *self {
+            Boundary::Clamp => value,
+            Boundary::Reflect if value < min => min + (min - value),
+            Boundary::Reflect if value > max => max - (value - max),
+            Boundary::Reflect => value,
+        };
+        value.max(min).min(max)
+    }
+}
+
+/// Computes the cost of a solution, to be minimized.
+pub type CostFunction = Fn(&[f64]) -> f64 + Send + Sync + 'static;
+
+/// Minimizes a function over a box-constrained space of real vectors.
+///
+/// New solutions are drawn uniformly from the bounds. To explore near a
+/// solution *x*, one dimension *j* and one other solution *x<sub>k</sub>*
+/// are chosen at random, and a variant *v* is produced, identical to *x*
+/// except that:
+///
+/// <center>*v<sub>j</sub>* = *x<sub>j</sub>* + φ (*x<sub>j</sub>* −
+/// *x<sub>kj</sub>*), with φ drawn uniformly from [-1, 1]</center>
+///
+/// If *v<sub>j</sub>* falls outside of its bounds, it is brought back in
+/// according to the context's [`Boundary`](enum.Boundary.html) setting.
+///
+/// With [`set_gbest_factor`](#method.set_gbest_factor), the context instead
+/// uses the gbest-guided update of Zhu and Kwong's GABC, which also pulls
+/// the variant towards the hive's best solution *g*:
+///
+/// <center>*v<sub>j</sub>* = *x<sub>j</sub>* + φ (*x<sub>j</sub>* −
+/// *x<sub>kj</sub>*) + ψ (*g<sub>j</sub>* − *x<sub>j</sub>*), with ψ drawn
+/// uniformly from [0, *C*]</center>
+///
+/// The context honours the hive's
+/// [modification rate](../struct.HiveBuilder.html#method.set_modification_rate),
+/// varying each dimension with that probability (and at least one), and
+/// draws φ from the [scale](../struct.HiveBuilder.html#method.set_phi_scale)
+/// set by the hive rather than from [-1, 1].
+///
+/// Costs are reported as the candidates' raw objective values, and converted
+/// to fitness as described for [`Objective::Minimize`](../enum.Objective.html).
+pub struct RealVectorContext {
+    bounds: Vec<(f64, f64)>,
+    objective: Box<CostFunction>,
+    boundary: Boundary,
+    gbest_factor: f64,
+}
+
+impl RealVectorContext {
+    /// Creates a context that will minimize `objective`.
+    ///
+    /// * `bounds` - Inclusive `(min, max)` bounds for each dimension.
+    /// * `objective` - Cost of a solution, to be minimized.
+    pub fn new<F>(bounds: Vec<(f64, f64)>, objective: F) -> RealVectorContext
+        where F: Fn(&[f64]) -> f64 + Send + Sync + 'static
+    {
+        if bounds.is_empty() {
+            panic!("RealVectorContext must have at least one dimension.");
+        }
+        if bounds.iter().any(|&(min, max)| min.is_nan() || max.is_nan() || min > max) {
+            panic!("RealVectorContext bounds must have min <= max.");
+        }
+
+        RealVectorContext {
+            bounds: bounds,
+            objective: Box::new(objective),
+            boundary: Boundary::Clamp,
+            gbest_factor: 0.0,
+        }
+    }
+
+    /// Sets how out-of-bounds values are handled.
+    ///
+    /// This defaults to `Boundary::Clamp`.
+    pub fn set_boundary(mut self, boundary: Boundary) -> RealVectorContext {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Sets the factor *C* that pulls variants towards the best solution.
+    ///
+    /// This defaults to 0, which gives the canonical ABC update. Zhu and Kwong
+    /// found *C* = 1.5 to work well across their benchmarks.
+    pub fn set_gbest_factor(mut self, factor: f64) -> RealVectorContext {
+        self.gbest_factor = factor;
+        self
+    }
+
+    /// Returns the bounds of each dimension.
+    pub fn bounds(&self) -> &[(f64, f64)] {
+        &self.bounds
+    }
+
+    /// Computes the cost of a solution.
+    pub fn cost(&self, solution: &[f64]) -> f64 {
+        (self.objective)(solution)
+    }
+
+    fn vary(&self,
+            field: &[Candidate<Vec<f64>>],
+            index: usize,
+            best: Option<&[f64]>,
+            modification_rate: Option<f64>,
+            phi_scale: f64,
+            rng: &mut HiveRng)
+            -> Vec<f64> {
+        let mut variant = field[index].solution.clone();
+        let other = &field[random_neighbour(field.len(), index, rng)].solution;
+
+        // Choose the dimensions to vary.
+        let mut dimensions = match modification_rate {
+            Some(rate) => (0..self.bounds.len()).filter(|_| rng.next_f64() < rate).collect(),
+            None => Vec::new(),
+        };
+        if dimensions.is_empty() {
+            dimensions.push(rng.gen_range(0, self.bounds.len()));
+        }
+
+        for j in dimensions {
+            let phi = rng.gen_range(-1.0, 1.0) * phi_scale;
+            let mut value = variant[j] + phi * (variant[j] - other[j]);
+            if let Some(best) = best {
+                let psi = rng.next_f64() * self.gbest_factor;
+                value += psi * (best[j] - variant[j]);
+            }
+            variant[j] = self.boundary.apply(value, self.bounds[j]);
+        }
+        variant
+    }
+}
+
+impl Context for RealVectorContext {
+    type Solution = Vec<f64>;
+
+    fn make(&self) -> Vec<f64> {
+        self.make_with_rng(&mut thread_rng().gen())
+    }
+
+    fn make_with_rng(&self, rng: &mut HiveRng) -> Vec<f64> {
+        self.bounds
+            .iter()
+            .map(|&(min, max)| min + rng.next_f64() * (max - min))
+            .collect()
+    }
+
+    fn evaluate_fitness(&self, solution: &Vec<f64>) -> f64 {
+        self.direction().fitness(self.evaluate_objective(solution))
+    }
+
+    fn evaluate_objective(&self, solution: &Vec<f64>) -> f64 {
+        self.cost(solution)
+    }
+
+    fn direction(&self) -> Objective {
+        Objective::Minimize
+    }
+
+    fn dimensions(&self) -> Option<usize> {
+        Some(self.bounds.len())
+    }
+
+    fn opposite(&self, solution: &Vec<f64>) -> Option<Vec<f64>> {
+        Some(solution.iter()
+                     .zip(&self.bounds)
+                     .map(|(x, &(min, max))| min + max - x)
+                     .collect())
+    }
+
+    fn solution_at(&self, point: &[f64]) -> Option<Vec<f64>> {
+        Some(point.iter()
+                  .zip(&self.bounds)
+                  .map(|(p, &(min, max))| min + p * (max - min))
+                  .collect())
+    }
+
+    fn explore(&self, field: &[Candidate<Vec<f64>>], index: usize) -> Vec<f64> {
+        self.explore_with_rng(field, index, &mut thread_rng().gen())
+    }
+
+    fn explore_with_rng(&self,
+                        field: &[Candidate<Vec<f64>>],
+                        index: usize,
+                        rng: &mut HiveRng)
+                        -> Vec<f64> {
+        self.vary(field, index, None, None, 1.0, rng)
+    }
+
+    fn explore_in(&self, exploration: &Exploration<Vec<f64>>, rng: &mut HiveRng) -> Vec<f64> {
+        let best = if self.gbest_factor != 0.0 {
+            Some(&exploration.best.solution[..])
+        } else {
+            None
+        };
+        self.vary(exploration.field,
+                  exploration.index,
+                  best,
+                  exploration.modification_rate,
+                  exploration.phi_scale,
+                  rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hive::HiveBuilder;
+
+    #[test]
+    fn boundaries() {
+        assert_eq!(Boundary::Clamp.apply(1.5, (0.0, 1.0)), 1.0);
+        assert_eq!(Boundary::Clamp.apply(-0.5, (0.0, 1.0)), 0.0);
+        assert_eq!(Boundary::Reflect.apply(1.25, (0.0, 1.0)), 0.75);
+        assert_eq!(Boundary::Reflect.apply(-0.25, (0.0, 1.0)), 0.25);
+        assert_eq!(Boundary::Reflect.apply(-5.0, (0.0, 1.0)), 1.0);
+        assert_eq!(Boundary::Reflect.apply(0.5, (0.0, 1.0)), 0.5);
+    }
+
+    #[test]
+    fn gbest_guided() {
+        let context = RealVectorContext::new(vec![(-5.0, 5.0); 10],
+                                             |x: &[f64]| x.iter().map(|xi| xi * xi).sum())
+                          .set_gbest_factor(1.5);
+        let hive = HiveBuilder::new(context, 10).set_threads(1).set_seed(7).build().unwrap();
+        let best = hive.run_for_rounds(300).unwrap();
+        assert!(best.objective < 0.01);
+    }
+
+    #[test]
+    fn modification_rate() {
+        let context = RealVectorContext::new(vec![(-5.0, 5.0); 10],
+                                             |x: &[f64]| x.iter().map(|xi| xi * xi).sum());
+        let hive = HiveBuilder::new(context, 10)
+                       .set_threads(1)
+                       .set_seed(7)
+                       .set_modification_rate(0.4)
+                       .build()
+                       .unwrap();
+        let best = hive.run_for_rounds(300).unwrap();
+        assert!(best.objective < 0.1);
+    }
+
+    #[test]
+    fn adaptive_phi() {
+        let context = RealVectorContext::new(vec![(-5.0, 5.0); 10],
+                                             |x: &[f64]| x.iter().map(|xi| xi * xi).sum());
+        let hive = HiveBuilder::new(context, 10)
+                       .set_threads(1)
+                       .set_seed(7)
+                       .set_phi_scale(0.5)
+                       .set_adaptive_phi(10)
+                       .build()
+                       .unwrap();
+        hive.run_for_rounds(100).unwrap();
+        assert!(hive.snapshot().unwrap().phi_scale != 0.5);
+    }
+
+    #[test]
+    fn minimizes_sphere() {
+        let context = RealVectorContext::new(vec![(-5.0, 5.0); 3],
+                                             |x: &[f64]| x.iter().map(|xi| xi * xi).sum())
+                          .set_boundary(Boundary::Reflect);
+        let hive = HiveBuilder::new(context, 10).set_threads(1).set_seed(7).build().unwrap();
+        let best = hive.run_for_rounds(200).unwrap();
+        assert!(best.solution.iter().all(|x| *x >= -5.0 && *x <= 5.0));
+        assert!(best.objective < 0.01);
+        assert_eq!(best.objective, hive.context().cost(&best.solution));
+    }
+}
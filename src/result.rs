@@ -1,33 +1,77 @@
+use std::any::Any;
 use std::result;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::PoisonError;
 use std::fmt;
 use std::error;
 
 #[derive(Debug)]
 /// Unifies the errors thrown by a hive's operation.
-///
-/// The only errors expected within the hive code are associated with getting
-/// read and/or write locks on aspects of the hive's data. These errors occur
-/// if a thread panics while holding the lock -- a situation that we do not
-/// particularly expect.
-///
-/// Nevertheless, this `Error` represents a panic, most likely in one of the
-/// hive's worker threads.
-pub struct Error;
+pub enum Error {
+    /// One of the hive's worker threads panicked while holding a lock.
+    ///
+    /// This poisons the lock, and leaves the hive's data in an unknown state.
+    Poisoned,
+
+    /// One of the context's methods panicked.
+    ///
+    /// The hive catches panics in the context's methods, so that they can be
+    /// reported without poisoning the hive's locks.
+    Panicked {
+        /// The method that panicked: `"make"`, `"evaluate"`,
+        /// `"constraint_violation"` or `"explore"`.
+        method: &'static str,
+
+        /// The panic's message, if it had one.
+        message: String,
+    },
+
+    /// The hive was built with settings that it cannot run with.
+    InvalidConfiguration(String),
+
+    /// A fitness, or a weight produced by scaling fitnesses, was NaN or
+    /// infinitely good, so candidates could no longer be compared.
+    ///
+    /// NaN fitnesses can be avoided with
+    /// [`HiveBuilder::set_nan_as_worst`](struct.HiveBuilder.html#method.set_nan_as_worst).
+    InvalidFitness(f64),
+
+    /// An error returned by user code.
+    User(Box<error::Error + Send + Sync>),
+}
 
 impl error::Error for Error {
     fn description(&self) -> &str {
-        "One of the hive's workers panicked."
+        match *self {
+            Error::Poisoned => "One of the hive's workers panicked.",
+            Error::Panicked { .. } => "One of the context's methods panicked.",
+            Error::InvalidConfiguration(_) => "The hive's configuration is invalid.",
+            Error::InvalidFitness(_) => "A fitness could not be compared.",
+            Error::User(_) => "User code returned an error.",
+        }
     }
 
-    fn cause(&self) -> Option<&error::Error> {
-        None
+    fn source(&self) -> Option<&(error::Error + 'static)> {
+        match *self {
+            Error::User(ref error) => Some(&**error),
+            _ => None,
+        }
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "One of the hive's workers panicked.")
+        match *self {
+            Error::Poisoned => write!(f, "One of the hive's workers panicked."),
+            Error::Panicked { method, ref message } => {
+                write!(f, "Context panicked in {}: {}", method, message)
+            }
+            Error::InvalidConfiguration(ref reason) => {
+                write!(f, "Invalid configuration: {}", reason)
+            }
+            Error::InvalidFitness(fitness) => write!(f, "Invalid fitness: {}.", fitness),
+            Error::User(ref error) => write!(f, "User error: {}", error),
+        }
     }
 }
 
@@ -36,9 +80,63 @@ impl fmt::Display for Error {
 // we abstract over them with T.
 impl<T> From<PoisonError<T>> for Error {
     fn from(_: PoisonError<T>) -> Error {
-        Error
+        Error::Poisoned
+    }
+}
+
+/// Runs one of the context's methods, turning a panic into an `Error`.
+///
+/// `method` names the method in the error.
+pub fn catch_panic<T, F>(method: &'static str, f: F) -> Result<T>
+    where F: FnOnce() -> T
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        Error::Panicked {
+            method: method,
+            message: panic_message(&*payload),
+        }
+    })
+}
+
+/// Extracts the message from a panic's payload, which is almost always a
+/// string of some sort.
+fn panic_message(payload: &(Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "(no message)".to_string()
     }
 }
 
-/// Encodes the possibility of a thread panicking and corruping a mutex.
+/// Encodes the possibility of a hive failing, most often because a thread
+/// panicked or a fitness could not be compared.
 pub type Result<T> = result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as StdError;
+    use std::io;
+
+    #[test]
+    fn captures_panic_message() {
+        match catch_panic("evaluate", || -> f64 { panic!("bad {}", 42) }) {
+            Err(Error::Panicked { method, message }) => {
+                assert_eq!(method, "evaluate");
+                assert_eq!(message, "bad 42");
+            }
+            other => panic!("Expected a panic, got {:?}", other),
+        }
+        assert_eq!(catch_panic("make", || 3).unwrap(), 3);
+    }
+
+    #[test]
+    fn chains_user_errors() {
+        let error = Error::User(Box::new(io::Error::new(io::ErrorKind::Other, "disk full")));
+        assert_eq!(error.to_string(), "User error: disk full");
+        assert_eq!(error.source().unwrap().to_string(), "disk full");
+        assert!(Error::Poisoned.source().is_none());
+    }
+}
@@ -80,15 +80,19 @@ pub fn rank() -> Box<ScalingFunction> {
 /// scaled<sub>*i*</sub> = rank<sub>*i*</sub><sup>*k*</sup>
 ///
 /// As with rank scaling, rank<sub>*i*</sub> starts with 1 for the least fit,
-/// and continues up to N for the most fit.
+/// and continues up to N for the most fit. NaN fitnesses are ranked below
+/// all others.
 pub fn power_rank(k: f64) -> Box<ScalingFunction> {
     Box::new(move |fitnesses: Vec<f64>| {
         // Pair each fitness with its index, so that we can remember which goes
         // where after sorting.
         let mut with_indices = fitnesses.iter().enumerate().collect::<Vec<_>>();
 
-        // Sort by fitness, ascending. After this, we can ignore fitness.
-        with_indices.sort_by(|&(_, f1), &(_, f2)| f1.partial_cmp(f2).unwrap());
+        // Sort by fitness, ascending, with any NaNs ranked least fit. After
+        // this, we can ignore fitness.
+        with_indices.sort_by(|&(_, f1), &(_, f2)| {
+            f1.partial_cmp(f2).unwrap_or_else(|| f2.is_nan().cmp(&f1.is_nan()))
+        });
 
         // The rank of solution i now corresponds to the index in with_indices
         // of (i, fitness_i). But we want the original index to be the index,
@@ -103,3 +107,14 @@ pub fn power_rank(k: f64) -> Box<ScalingFunction> {
         ranks
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::NAN;
+
+    #[test]
+    fn rank_puts_nan_last() {
+        assert_eq!(rank()(vec![2.0, NAN, -1.0, NAN]), vec![4.0, 1.0, 3.0, 2.0]);
+    }
+}
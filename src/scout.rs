@@ -0,0 +1,26 @@
+/// When a hive abandons candidates that have run out of retries.
+///
+/// Set the policy with
+/// [`HiveBuilder::set_scout_policy`](struct.HiveBuilder.html#method.set_scout_policy).
+/// Whatever the policy, a candidate is only abandoned once it has gone
+/// unimproved as many times as the hive allows, and is replaced by a new
+/// one, found by a scout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoutPolicy {
+    /// Abandon a candidate as soon as it runs out of retries.
+    ///
+    /// This is the default in asynchronous mode.
+    Immediate,
+
+    /// At the end of each round, abandon the most exhausted of the
+    /// candidates that have run out of retries, if there are any.
+    ///
+    /// This is Karaboga's canonical algorithm, and the default in
+    /// synchronous mode. The other exhausted candidates wait for later
+    /// rounds, unless they improve in the meantime.
+    OnePerCycle,
+
+    /// At the end of each round, abandon every candidate that has run out
+    /// of retries.
+    AllExhaustedPerCycle,
+}
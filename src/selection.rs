@@ -0,0 +1,214 @@
+//! Chooses the solutions that observers will work on.
+//!
+//! Once a [`ScalingFunction`](../scaling/type.ScalingFunction.html) has turned
+//! the candidates' fitnesses into weights, a [`Selection`](trait.Selection.html)
+//! strategy uses those weights to pick the candidates that the observers will
+//! work on. Keeping the two separate means that selection pressure can be
+//! tuned without changing the scaling, and vice versa.
+//!
+//! Each observer's choice is usually made on its own, from the field as it is
+//! when the observer starts work. Strategies that spread a whole round's
+//! choices across the field, like
+//! [stochastic universal sampling](fn.stochastic_universal.html), make them a
+//! round at a time instead: whenever the hive runs out of choices, it asks
+//! the strategy for as many as there are observers, and hands them out in
+//! turn. Candidates that are being scouted are never offered to the strategy.
+//!
+//! By default, [roulette](fn.roulette.html) selection is used.
+//!
+//! # Examples
+//!
+//! Several strategies are available in this module:
+//!
+//! ```
+//! # extern crate abc; fn main() {
+//! use abc::selection;
+//!
+//! // Choose the better of two random candidates.
+//! selection::tournament(2);
+//! # }
+//! ```
+//!
+//! Users may also write their own strategies. Any closure that takes the
+//! weights, a number of choices and a random number generator, and returns
+//! positions in the weights, will do:
+//!
+//! ```
+//! # extern crate abc; fn main() {
+//! use abc::HiveRng;
+//!
+//! // Always choose the heaviest candidate.
+//! Box::new(|weights: &[f64], count: usize, _: &mut HiveRng| {
+//!     let best = (0..weights.len()).fold(0, |b, i| if weights[i] > weights[b] { i } else { b });
+//!     vec![best; count]
+//! });
+//! # }
+//! ```
+
+extern crate rand;
+
+use self::rand::Rng;
+
+use context::HiveRng;
+
+/// Chooses candidates for observers to work on.
+pub trait Selection: Send + Sync {
+    /// Chooses `count` positions in `weights`, which is never empty.
+    ///
+    /// Positions may be chosen more than once, and may be returned in any
+    /// order.
+    fn select(&self, weights: &[f64], count: usize, rng: &mut HiveRng) -> Vec<usize>;
+
+    /// Whether a whole round's choices must be made at once.
+    ///
+    /// If so, the hive asks for as many choices as there are observers, and
+    /// hands them out in turn, even if the field changes in the meantime.
+    /// Otherwise, it asks for one choice per observer.
+    ///
+    /// By default, this is `false`.
+    fn whole_round(&self) -> bool {
+        false
+    }
+}
+
+impl<F> Selection for F
+    where F: Fn(&[f64], usize, &mut HiveRng) -> Vec<usize> + Send + Sync
+{
+    fn select(&self, weights: &[f64], count: usize, rng: &mut HiveRng) -> Vec<usize> {
+        self(weights, count, rng)
+    }
+}
+
+/// Finds the position of `point` along the running total of `weights`.
+fn locate(weights: &[f64], point: f64) -> usize {
+    let mut total = 0_f64;
+    for (i, weight) in weights.iter().enumerate() {
+        total += *weight;
+        if total > point {
+            return i;
+        }
+    }
+    // Rounding can leave the point just past the end.
+    weights.len() - 1
+}
+
+/// Chooses each candidate with probability proportionate to its weight.
+///
+/// Each choice spins the roulette wheel independently. If no candidate has
+/// positive weight, choices are made uniformly at random.
+///
+/// P(*i*) = weight<sub>*i*</sub> / ∑<sub>*j*</sub> weight<sub>*j*</sub>
+pub fn roulette() -> Box<Selection> {
+    Box::new(|weights: &[f64], count: usize, rng: &mut HiveRng| {
+        let total = weights.iter().sum::<f64>();
+        (0..count)
+            .map(|_| {
+                if total > 0_f64 {
+                    locate(weights, rng.next_f64() * total)
+                } else {
+                    rng.gen_range(0, weights.len())
+                }
+            })
+            .collect::<Vec<usize>>()
+    })
+}
+
+/// Chooses candidates with Baker's stochastic universal sampling.
+///
+/// The expected number of choices for each candidate is the same as with
+/// [roulette](fn.roulette.html) selection, but all of the round's choices
+/// are made with a single spin of a wheel with `count` evenly spaced
+/// pointers. This keeps the actual number of choices within one of the
+/// expected number.
+pub fn stochastic_universal() -> Box<Selection> {
+    Box::new(StochasticUniversal)
+}
+
+struct StochasticUniversal;
+
+impl Selection for StochasticUniversal {
+    fn select(&self, weights: &[f64], count: usize, rng: &mut HiveRng) -> Vec<usize> {
+        let total = weights.iter().sum::<f64>();
+        if total <= 0_f64 || count == 0 {
+            return (0..count).map(|_| rng.gen_range(0, weights.len())).collect();
+        }
+
+        let step = total / count as f64;
+        let start = rng.next_f64() * step;
+        let mut chosen = (0..count)
+                             .map(|i| locate(weights, start + i as f64 * step))
+                             .collect::<Vec<usize>>();
+        // Spread each candidate's choices across the round.
+        rng.shuffle(&mut chosen);
+        chosen
+    }
+
+    fn whole_round(&self) -> bool {
+        true
+    }
+}
+
+/// Chooses the heaviest of `k` candidates drawn uniformly at random.
+///
+/// Larger tournaments apply more selection pressure. Since only the order of
+/// the weights matters, tournaments work just as well with negative weights.
+pub fn tournament(k: usize) -> Box<Selection> {
+    if k == 0 {
+        panic!("Tournament selection needs at least one entrant.");
+    }
+
+    Box::new(move |weights: &[f64], count: usize, rng: &mut HiveRng| {
+        (0..count)
+            .map(|_| {
+                let mut winner = rng.gen_range(0, weights.len());
+                for _ in 1..k {
+                    let entrant = rng.gen_range(0, weights.len());
+                    if weights[entrant] > weights[winner] {
+                        winner = entrant;
+                    }
+                }
+                winner
+            })
+            .collect::<Vec<usize>>()
+    })
+}
+
+/// Chooses candidates uniformly at random, regardless of their weights.
+pub fn uniform() -> Box<Selection> {
+    Box::new(|weights: &[f64], count: usize, rng: &mut HiveRng| {
+        (0..count).map(|_| rng.gen_range(0, weights.len())).collect::<Vec<usize>>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use self::rand::SeedableRng;
+
+    fn counts(selection: Box<Selection>, weights: &[f64], count: usize) -> Vec<usize> {
+        let mut rng = HiveRng::from_seed([1, 2, 3, 4]);
+        let mut counts = vec![0; weights.len()];
+        for i in selection.select(weights, count, &mut rng) {
+            counts[i] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn stochastic_universal_is_exact() {
+        assert_eq!(counts(stochastic_universal(), &[1.0, 2.0, 3.0, 4.0], 10),
+                   vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn roulette_ignores_zero_weights() {
+        assert_eq!(counts(roulette(), &[0.0, 1.0, 0.0], 100)[1], 100);
+        assert_eq!(counts(roulette(), &[0.0, 0.0], 100).iter().sum::<usize>(), 100);
+    }
+
+    #[test]
+    fn tournament_handles_negative_weights() {
+        let counts = counts(tournament(3), &[-3.0, -1.0, -2.0], 100);
+        assert!(counts[1] > counts[2] && counts[2] > counts[0]);
+    }
+}
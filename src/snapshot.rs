@@ -0,0 +1,42 @@
+use candidate::Candidate;
+
+/// Saved state of a hive, from which an equivalent hive can be rebuilt.
+///
+/// Take a snapshot with [`Hive::snapshot`](struct.Hive.html#method.snapshot),
+/// and resume from it with
+/// [`HiveBuilder::resume_from`](struct.HiveBuilder.html#method.resume_from).
+/// A resumed hive picks up the saved candidates as they were, without
+/// calling [`Context::make`](trait.Context.html#method.make) again.
+///
+/// With the `serde` feature enabled, snapshots can be serialized, so that a
+/// long run can be checkpointed to disk and resumed after a crash. The state
+/// of the hive's random number generators is not saved; a resumed hive gets
+/// fresh generators, seeded as usual.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Snapshot<S: Clone + Send + Sync + 'static> {
+    /// Working candidates, in order.
+    pub candidates: Vec<Candidate<S>>,
+
+    /// Number of times each working candidate can still go unimproved before
+    /// it is abandoned.
+    pub retries: Vec<i32>,
+
+    /// Best candidate found so far.
+    pub best: Candidate<S>,
+
+    /// Non-dominated candidates, if the hive keeps a
+    /// [Pareto archive](struct.HiveBuilder.html#method.set_pareto_archive).
+    pub archive: Vec<Candidate<S>>,
+
+    /// Number of rounds completed.
+    pub rounds: usize,
+
+    /// Number of fitness evaluations performed.
+    pub evaluations: usize,
+
+    /// Current scale of the random factor φ.
+    ///
+    /// See [`HiveBuilder::set_adaptive_phi`](struct.HiveBuilder.html#method.set_adaptive_phi).
+    pub phi_scale: f64,
+}
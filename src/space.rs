@@ -0,0 +1,538 @@
+//! Declarative parameter spaces, for tuning mixed sets of parameters.
+//!
+//! Hyperparameter tuning often searches over a mixture of real values,
+//! real values that vary over orders of magnitude, bounded integers, and
+//! choices from a fixed set of options. A [`SearchSpace`](struct.SearchSpace.html)
+//! describes such a set of parameters, and
+//! [`into_context`](struct.SearchSpace.html#method.into_context) turns it,
+//! together with an objective function, into a
+//! [`Context`](../trait.Context.html) whose solutions are
+//! [`Params`](struct.Params.html).
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate abc; fn main() {
+//! use abc::HiveBuilder;
+//! use abc::space::{SearchSpace, Params};
+//!
+//! let space = SearchSpace::new()
+//!                 .float("momentum", 0.0, 1.0)
+//!                 .log_float("learning_rate", 1e-5, 1e-1)
+//!                 .int("layers", 1, 8)
+//!                 .categorical("activation", &["relu", "tanh", "sigmoid"]);
+//!
+//! // Minimize a made-up validation loss.
+//! let context = space.into_context(|params: &Params| {
+//!     let penalty = if params.choice("activation") == Some("relu") { 0.0 } else { 1.0 };
+//!     (params.float("learning_rate").unwrap().log10() + 3.0).abs() +
+//!     (params.int("layers").unwrap() - 4).abs() as f64 +
+//!     params.float("momentum").unwrap() + penalty
+//! });
+//!
+//! let hive = HiveBuilder::new(context, 10).build().unwrap();
+//! let best = hive.run_for_rounds(50).unwrap();
+//! println!("{:?} has loss {}", best.solution, best.objective);
+//! # }
+//! ```
+
+extern crate rand;
+
+use std::collections::BTreeMap;
+
+use self::rand::{thread_rng, Rng};
+
+use candidate::Candidate;
+use context::{Context, HiveRng, Exploration, random_neighbour};
+use objective::Objective;
+
+/// Computes the objective value of a set of parameters.
+pub type ParamsFunction = Fn(&Params) -> f64 + Send + Sync + 'static;
+
+/// Kind and range of a single parameter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Parameter {
+    /// Real value, drawn uniformly from `[min, max]`.
+    Float(f64, f64),
+
+    /// Positive real value whose logarithm is drawn uniformly from
+    /// `[ln min, ln max]`.
+    LogFloat(f64, f64),
+
+    /// Integer, drawn uniformly from `min..=max`.
+    Int(i64, i64),
+
+    /// One of a fixed set of options.
+    Categorical(Vec<String>),
+}
+
+/// Value of a single parameter.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Value {
+    /// Value of a `Float` or `LogFloat` parameter.
+    Float(f64),
+
+    /// Value of an `Int` parameter.
+    Int(i64),
+
+    /// Chosen option of a `Categorical` parameter.
+    Choice(String),
+}
+
+/// Values for each of the parameters in a search space, by name.
+#[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Params {
+    values: BTreeMap<String, Value>,
+}
+
+impl Params {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Params {
+        Params { values: BTreeMap::new() }
+    }
+
+    /// Sets the value of the named parameter.
+    ///
+    /// Together with [`new`](#method.new), this builds known solutions for
+    /// [`HiveBuilder::add_known_solution`](../struct.HiveBuilder.html#method.add_known_solution).
+    /// A known solution should set every parameter in the space, to a value
+    /// of the parameter's kind.
+    pub fn set(mut self, name: &str, value: Value) -> Params {
+        self.values.insert(name.to_string(), value);
+        self
+    }
+
+    /// Returns the value of the named parameter.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+
+    /// Returns the value of the named `Float` or `LogFloat` parameter.
+    pub fn float(&self, name: &str) -> Option<f64> {
+        match self.get(name) {
+            Some(&Value::Float(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the named `Int` parameter.
+    pub fn int(&self, name: &str) -> Option<i64> {
+        match self.get(name) {
+            Some(&Value::Int(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the chosen option of the named `Categorical` parameter.
+    pub fn choice(&self, name: &str) -> Option<&str> {
+        match self.get(name) {
+            Some(Value::Choice(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the parameters, in order of name.
+    pub fn iter(&self) -> ::std::collections::btree_map::Iter<'_, String, Value> {
+        self.values.iter()
+    }
+}
+
+/// Describes a set of named parameters to be tuned.
+#[derive(Clone, Debug, Default)]
+pub struct SearchSpace {
+    parameters: Vec<(String, Parameter)>,
+}
+
+impl SearchSpace {
+    /// Creates an empty search space.
+    pub fn new() -> SearchSpace {
+        SearchSpace { parameters: Vec::new() }
+    }
+
+    /// Adds a parameter to the space.
+    pub fn parameter(mut self, name: &str, parameter: Parameter) -> SearchSpace {
+        if self.parameters.iter().any(|(existing, _)| existing == name) {
+            panic!("SearchSpace already has a parameter named {:?}.", name);
+        }
+        match parameter {
+            Parameter::Float(min, max) | Parameter::LogFloat(min, max)
+                if min.is_nan() || max.is_nan() || min > max => {
+                panic!("SearchSpace parameter {:?} must have min <= max.", name)
+            }
+            Parameter::LogFloat(min, _) if min <= 0.0 => {
+                panic!("SearchSpace parameter {:?} must be positive to be log-scaled.", name)
+            }
+            Parameter::Int(min, max) if min > max => {
+                panic!("SearchSpace parameter {:?} must have min <= max.", name)
+            }
+            Parameter::Categorical(ref options) if options.is_empty() => {
+                panic!("SearchSpace parameter {:?} must have at least one option.", name)
+            }
+            _ => {}
+        }
+
+        self.parameters.push((name.to_string(), parameter));
+        self
+    }
+
+    /// Adds a real-valued parameter in `[min, max]`.
+    pub fn float(self, name: &str, min: f64, max: f64) -> SearchSpace {
+        self.parameter(name, Parameter::Float(min, max))
+    }
+
+    /// Adds a positive real-valued parameter in `[min, max]`, searched on a
+    /// logarithmic scale.
+    pub fn log_float(self, name: &str, min: f64, max: f64) -> SearchSpace {
+        self.parameter(name, Parameter::LogFloat(min, max))
+    }
+
+    /// Adds an integer parameter in `min..=max`.
+    pub fn int(self, name: &str, min: i64, max: i64) -> SearchSpace {
+        self.parameter(name, Parameter::Int(min, max))
+    }
+
+    /// Adds a parameter that takes one of `options`.
+    pub fn categorical(self, name: &str, options: &[&str]) -> SearchSpace {
+        let options = options.iter().map(|option| option.to_string()).collect();
+        self.parameter(name, Parameter::Categorical(options))
+    }
+
+    /// Returns the parameters in the space, in the order they were added.
+    pub fn parameters(&self) -> &[(String, Parameter)] {
+        &self.parameters
+    }
+
+    /// Creates a context that will minimize `objective` over this space.
+    pub fn into_context<F>(self, objective: F) -> SpaceContext
+        where F: Fn(&Params) -> f64 + Send + Sync + 'static
+    {
+        if self.parameters.is_empty() {
+            panic!("SearchSpace must have at least one parameter.");
+        }
+
+        SpaceContext {
+            space: self,
+            objective: Box::new(objective),
+            direction: Objective::Minimize,
+        }
+    }
+}
+
+/// Optimizes a function over the parameters of a [`SearchSpace`](struct.SearchSpace.html).
+///
+/// New solutions draw each parameter at random from its range. To explore
+/// near a solution, another random solution *k* is chosen, along with the
+/// parameters to vary: each with the hive's
+/// [modification rate](../struct.HiveBuilder.html#method.set_modification_rate)
+/// if it has one, and at least one, or otherwise a single parameter at
+/// random. Each chosen parameter moves with respect to the same parameter of
+/// solution *k*, according to its kind:
+///
+/// * `Float` values move by φ (*x* − *x<sub>k</sub>*), as in canonical ABC,
+///   with φ drawn uniformly from the hive's
+///   [scale](../struct.HiveBuilder.html#method.set_phi_scale), [-1, 1] by
+///   default, and are clamped to their range.
+/// * `LogFloat` values make the same move on their logarithms.
+/// * `Int` values make the same move, rounded to the nearest integer. If
+///   this does not change the value, it steps up or down by one instead.
+/// * `Categorical` values take the choice of solution *k*, if it differs;
+///   otherwise, they switch to another option at random.
+///
+/// By default, the objective is minimized.
+pub struct SpaceContext {
+    space: SearchSpace,
+    objective: Box<ParamsFunction>,
+    direction: Objective,
+}
+
+impl SpaceContext {
+    /// Sets whether the objective should be minimized or maximized.
+    ///
+    /// This defaults to `Objective::Minimize`.
+    pub fn set_direction(mut self, direction: Objective) -> SpaceContext {
+        self.direction = direction;
+        self
+    }
+
+    /// Returns the search space.
+    pub fn space(&self) -> &SearchSpace {
+        &self.space
+    }
+
+    fn vary(&self,
+            field: &[Candidate<Params>],
+            index: usize,
+            modification_rate: Option<f64>,
+            phi_scale: f64,
+            rng: &mut HiveRng)
+            -> Params {
+        let mut variant = field[index].solution.clone();
+        let other = &field[random_neighbour(field.len(), index, rng)].solution;
+
+        // Choose the parameters to vary.
+        let parameters = &self.space.parameters;
+        let mut chosen = match modification_rate {
+            Some(rate) => (0..parameters.len()).filter(|_| rng.next_f64() < rate).collect(),
+            None => Vec::new(),
+        };
+        if chosen.is_empty() {
+            chosen.push(rng.gen_range(0, parameters.len()));
+        }
+
+        for j in chosen {
+            let (ref name, ref parameter) = parameters[j];
+            let value = match (variant.get(name), other.get(name)) {
+                (Some(current), Some(theirs)) => {
+                    vary(parameter, current, theirs, phi_scale, rng)
+                }
+                _ => draw(parameter, rng),
+            };
+            variant.values.insert(name.clone(), value);
+        }
+        variant
+    }
+}
+
+fn draw(parameter: &Parameter, rng: &mut HiveRng) -> Value {
+    match *parameter {
+        Parameter::Float(min, max) => Value::Float(min + rng.next_f64() * (max - min)),
+        Parameter::LogFloat(min, max) => {
+            let (min, max) = (min.ln(), max.ln());
+            Value::Float((min + rng.next_f64() * (max - min)).exp())
+        }
+        Parameter::Int(min, max) => {
+            // Widened, so that ranges as large as all of `i64` can be drawn.
+            let span = (max as i128 - min as i128 + 1) as u128;
+            let offset = if span > u64::MAX as u128 {
+                rng.gen::<u64>()
+            } else {
+                rng.gen_range(0, span as u64)
+            };
+            Value::Int((min as i128 + offset as i128) as i64)
+        }
+        Parameter::Categorical(ref options) => {
+            Value::Choice(options[rng.gen_range(0, options.len())].clone())
+        }
+    }
+}
+
+fn vary(parameter: &Parameter,
+        current: &Value,
+        other: &Value,
+        phi_scale: f64,
+        rng: &mut HiveRng)
+        -> Value {
+    let phi = rng.gen_range(-1.0, 1.0) * phi_scale;
+    match (parameter, current, other) {
+        (&Parameter::Float(min, max), &Value::Float(x), &Value::Float(k)) => {
+            Value::Float((x + phi * (x - k)).max(min).min(max))
+        }
+        (&Parameter::LogFloat(min, max), &Value::Float(x), &Value::Float(k)) => {
+            let (x, k) = (x.ln(), k.ln());
+            Value::Float((x + phi * (x - k)).exp().max(min).min(max))
+        }
+        (&Parameter::Int(min, max), &Value::Int(x), &Value::Int(k)) => {
+            // Casting back from `f64` saturates at the bounds of `i64`.
+            let mut value = (x as f64 + phi * (x as f64 - k as f64)).round() as i64;
+            if value == x {
+                value = if rng.gen() { x.saturating_add(1) } else { x.saturating_sub(1) };
+            }
+            Value::Int(value.max(min).min(max))
+        }
+        (Parameter::Categorical(options), Value::Choice(x), Value::Choice(k)) => {
+            if x != k {
+                Value::Choice(k.clone())
+            } else if options.len() > 1 {
+                let position = options.iter().position(|option| option == x).unwrap_or(0);
+                let choice = random_neighbour(options.len(), position, rng);
+                Value::Choice(options[choice].clone())
+            } else {
+                current.clone()
+            }
+        }
+        _ => draw(parameter, rng),
+    }
+}
+
+impl Context for SpaceContext {
+    type Solution = Params;
+
+    fn make(&self) -> Params {
+        self.make_with_rng(&mut thread_rng().gen())
+    }
+
+    fn make_with_rng(&self, rng: &mut HiveRng) -> Params {
+        Params {
+            values: self.space
+                        .parameters
+                        .iter()
+                        .map(|(name, parameter)| (name.clone(), draw(parameter, rng)))
+                        .collect(),
+        }
+    }
+
+    fn evaluate_fitness(&self, solution: &Params) -> f64 {
+        self.direction().fitness(self.evaluate_objective(solution))
+    }
+
+    fn evaluate_objective(&self, solution: &Params) -> f64 {
+        (self.objective)(solution)
+    }
+
+    fn direction(&self) -> Objective {
+        self.direction
+    }
+
+    fn dimensions(&self) -> Option<usize> {
+        Some(self.space.parameters.len())
+    }
+
+    fn explore(&self, field: &[Candidate<Params>], index: usize) -> Params {
+        self.explore_with_rng(field, index, &mut thread_rng().gen())
+    }
+
+    fn explore_with_rng(&self,
+                        field: &[Candidate<Params>],
+                        index: usize,
+                        rng: &mut HiveRng)
+                        -> Params {
+        self.vary(field, index, None, 1.0, rng)
+    }
+
+    fn explore_in(&self, exploration: &Exploration<Params>, rng: &mut HiveRng) -> Params {
+        self.vary(exploration.field,
+                  exploration.index,
+                  exploration.modification_rate,
+                  exploration.phi_scale,
+                  rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hive::HiveBuilder;
+
+    #[test]
+    fn values_stay_in_range() {
+        let space = SearchSpace::new()
+                        .float("x", -1.0, 1.0)
+                        .log_float("y", 1e-3, 1e3)
+                        .int("z", 0, 3)
+                        .categorical("w", &["a", "b"]);
+        let context = space.into_context(|params: &Params| {
+            params.float("x").unwrap().abs() + (params.int("z").unwrap() - 2).abs() as f64
+        });
+        let hive = HiveBuilder::new(context, 10).set_threads(1).set_seed(11).build().unwrap();
+        hive.run_for_rounds(100).unwrap();
+
+        let snapshot = hive.snapshot().unwrap();
+        for params in snapshot.candidates.iter().map(|c| &c.solution) {
+            let x = params.float("x").unwrap();
+            let y = params.float("y").unwrap();
+            let z = params.int("z").unwrap();
+            assert!(x >= -1.0 && x <= 1.0);
+            assert!(y >= 1e-3 && y <= 1e3);
+            assert!(z >= 0 && z <= 3);
+            assert!(params.choice("w") == Some("a") || params.choice("w") == Some("b"));
+        }
+        assert_eq!(snapshot.best.solution.int("z"), Some(2));
+    }
+
+    #[test]
+    fn extreme_int_bounds() {
+        let space = SearchSpace::new()
+                        .int("all", i64::MIN, i64::MAX)
+                        .int("top", i64::MAX - 1, i64::MAX)
+                        .int("bottom", i64::MIN, i64::MIN + 1);
+        let context = space.into_context(|params: &Params| {
+            (params.int("top").unwrap() - (i64::MAX - 1)) as f64
+        });
+        let hive = HiveBuilder::new(context, 10).set_threads(1).set_seed(5).build().unwrap();
+        hive.run_for_rounds(50).unwrap();
+
+        let snapshot = hive.snapshot().unwrap();
+        for params in snapshot.candidates.iter().map(|c| &c.solution) {
+            assert!(params.int("all").is_some());
+            assert!(params.int("top").unwrap() >= i64::MAX - 1);
+            assert!(params.int("bottom").unwrap() <= i64::MIN + 1);
+        }
+    }
+
+    #[test]
+    fn modification_rate_varies_every_parameter() {
+        use hive::derive_rng;
+
+        let space = SearchSpace::new().int("a", 0, 100).int("b", 0, 100).int("c", 0, 100);
+        let context = space.into_context(|_: &Params| 0.0);
+        let params = |value: i64| {
+            Params {
+                values: ["a", "b", "c"]
+                            .iter()
+                            .map(|name| (name.to_string(), Value::Int(value)))
+                            .collect(),
+            }
+        };
+        let field = vec![Candidate::new(params(50), 0.0), Candidate::new(params(60), 0.0)];
+        let mut rng = derive_rng(3, 0);
+        let exploration = Exploration {
+            field: &field,
+            index: 0,
+            best: &field[1],
+            modification_rate: Some(1.0),
+            phi_scale: 1.0,
+        };
+        let variant = context.explore_in(&exploration, &mut rng);
+        assert!(variant.iter().zip(field[0].solution.iter()).all(|(x, y)| x != y));
+    }
+
+    #[test]
+    fn phi_scale_narrows_moves() {
+        use hive::derive_rng;
+
+        let space = SearchSpace::new().float("x", -100.0, 100.0);
+        let context = space.into_context(|_: &Params| 0.0);
+        let params = |value: f64| {
+            Params { values: Some(("x".to_string(), Value::Float(value))).into_iter().collect() }
+        };
+        let field = vec![Candidate::new(params(0.0), 0.0), Candidate::new(params(10.0), 0.0)];
+        let mut rng = derive_rng(3, 0);
+        let exploration = Exploration {
+            field: &field,
+            index: 0,
+            best: &field[1],
+            modification_rate: None,
+            phi_scale: 0.25,
+        };
+        for _ in 0..100 {
+            let variant = context.explore_in(&exploration, &mut rng);
+            assert!(variant.float("x").unwrap().abs() <= 2.5);
+        }
+    }
+
+    #[test]
+    fn known_params() {
+        let space = SearchSpace::new().int("x", -1000000, 1000000).categorical("y", &["a", "b"]);
+        let context = space.into_context(|params: &Params| {
+            (params.int("x").unwrap() - 123456).abs() as f64
+        });
+        let known = Params::new()
+                        .set("x", Value::Int(123456))
+                        .set("y", Value::Choice("b".to_string()));
+        let hive = HiveBuilder::new(context, 10)
+                       .set_threads(1)
+                       .add_known_solution(known.clone())
+                       .build()
+                       .unwrap();
+        assert_eq!(hive.get().unwrap().solution, known);
+        assert_eq!(hive.get().unwrap().objective, 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_names() {
+        SearchSpace::new().int("x", 0, 1).float("x", 0.0, 1.0);
+    }
+}
@@ -0,0 +1,28 @@
+/// Running totals describing the work a hive has done.
+///
+/// These are cumulative over the life of the hive, across every run. See
+/// [`Hive::statistics`](struct.Hive.html#method.statistics).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    /// Number of rounds completed.
+    pub rounds: usize,
+
+    /// Number of fitness evaluations performed, including the evaluations of
+    /// the initial candidates and of the new candidates found by scouts.
+    /// Solutions answered by the evaluation cache are not counted.
+    pub evaluations: usize,
+
+    /// Number of evaluations answered by the hive's evaluation cache.
+    ///
+    /// See [`HiveBuilder::set_evaluation_cache`](struct.HiveBuilder.html#method.set_evaluation_cache).
+    /// Without a cache, this is zero.
+    pub cache_hits: usize,
+
+    /// Number of evaluations that missed the hive's evaluation cache, and
+    /// were passed on to the context.
+    ///
+    /// Each solution is counted once, whether or not its evaluation
+    /// succeeded; retries are not counted again, although they do count
+    /// towards `evaluations`.
+    pub cache_misses: usize,
+}
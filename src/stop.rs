@@ -0,0 +1,187 @@
+//! Decides when a running hive should stop.
+//!
+//! [`Hive::run_for_rounds`](../struct.Hive.html#method.run_for_rounds) and
+//! [`Hive::run_forever`](../struct.Hive.html#method.run_forever) cover the
+//! simplest cases. For anything else, pass a
+//! [`StopCondition`](trait.StopCondition.html) to
+//! [`Hive::run_until`](../struct.Hive.html#method.run_until). Before each bee
+//! starts work, the hive gathers a [`Status`](struct.Status.html) describing
+//! the run so far, and stops as soon as the condition is met.
+//!
+//! # Examples
+//!
+//! Several constructors for stop conditions are available in this module,
+//! and they can be combined with [`any`](fn.any.html) and
+//! [`all`](fn.all.html):
+//!
+//! ```
+//! # extern crate abc; fn main() {
+//! use std::time::Duration;
+//! use abc::stop;
+//!
+//! // Stop on reaching a fitness of 100, or after a minute, whichever
+//! // comes first.
+//! stop::any(vec![stop::target_fitness(100_f64),
+//!                stop::time_limit(Duration::from_secs(60))]);
+//! # }
+//! ```
+//!
+//! Users may also write their own conditions. Any closure that takes a
+//! `&Status` and returns a `bool` will do:
+//!
+//! ```
+//! # extern crate abc; fn main() {
+//! use abc::stop::Status;
+//!
+//! Box::new(|status: &Status| status.round >= 10 && status.best_fitness > 0.5);
+//! # }
+//! ```
+
+use std::time::Duration;
+
+/// Progress of a running hive, as seen by a stop condition.
+///
+/// Rounds, evaluations and elapsed time are all counted from the start of
+/// the current run.
+#[derive(Clone, Debug)]
+pub struct Status {
+    /// Current round, as reported by
+    /// [`Hive::get_round`](../struct.Hive.html#method.get_round).
+    pub round: usize,
+
+    /// Number of fitness evaluations performed so far.
+    pub evaluations: usize,
+
+    /// Fitness of the best candidate found so far.
+    pub best_fitness: f64,
+
+    /// Time since the run started.
+    pub elapsed: Duration,
+}
+
+/// Determines whether a hive should stop running.
+///
+/// Conditions are checked often, from whichever worker thread is about to
+/// start work, so `should_stop` should be cheap. A condition is only ever
+/// checked by one thread at a time, and may keep state between checks.
+pub trait StopCondition: Send {
+    /// Returns `true` once the hive should stop.
+    fn should_stop(&mut self, status: &Status) -> bool;
+}
+
+impl<F> StopCondition for F
+    where F: FnMut(&Status) -> bool + Send
+{
+    fn should_stop(&mut self, status: &Status) -> bool {
+        self(status)
+    }
+}
+
+/// Stops once the best candidate's fitness reaches `fitness`.
+pub fn target_fitness(fitness: f64) -> Box<StopCondition> {
+    Box::new(move |status: &Status| status.best_fitness >= fitness)
+}
+
+/// Stops once the run has lasted for `limit`.
+pub fn time_limit(limit: Duration) -> Box<StopCondition> {
+    Box::new(move |status: &Status| status.elapsed >= limit)
+}
+
+/// Stops once `rounds` rounds have started.
+pub fn max_rounds(rounds: usize) -> Box<StopCondition> {
+    Box::new(move |status: &Status| status.round >= rounds)
+}
+
+/// Stops once `evaluations` fitness evaluations have been performed.
+///
+/// Evaluations that are already in progress when the condition is met will
+/// still finish, so the final count may overshoot slightly. Use
+/// [`Hive::run_for_evaluations`](../struct.Hive.html#method.run_for_evaluations)
+/// to stop at an exact count.
+pub fn max_evaluations(evaluations: usize) -> Box<StopCondition> {
+    Box::new(move |status: &Status| status.evaluations >= evaluations)
+}
+
+/// Stops once the best fitness has not improved for `rounds` rounds.
+pub fn stagnation(rounds: usize) -> Box<StopCondition> {
+    // Best fitness so far, and the round in which it was first seen.
+    let mut best: Option<(f64, usize)> = None;
+    Box::new(move |status: &Status| {
+        let (fitness, since) = match best {
+            Some((fitness, since)) if status.best_fitness <= fitness => (fitness, since),
+            _ => (status.best_fitness, status.round),
+        };
+        best = Some((fitness, since));
+        status.round - since >= rounds
+    })
+}
+
+/// Stops once any of `conditions` is met.
+///
+/// Every condition is checked each time, so that stateful conditions (like
+/// [`stagnation`](fn.stagnation.html)) keep up with the run.
+pub fn any(mut conditions: Vec<Box<StopCondition>>) -> Box<StopCondition> {
+    Box::new(move |status: &Status| {
+        let mut stop = false;
+        for condition in &mut conditions {
+            if condition.should_stop(status) {
+                stop = true;
+            }
+        }
+        stop
+    })
+}
+
+/// Stops once all of `conditions` are met.
+///
+/// Every condition is checked each time, so that stateful conditions (like
+/// [`stagnation`](fn.stagnation.html)) keep up with the run.
+pub fn all(mut conditions: Vec<Box<StopCondition>>) -> Box<StopCondition> {
+    Box::new(move |status: &Status| {
+        let mut stop = true;
+        for condition in &mut conditions {
+            if !condition.should_stop(status) {
+                stop = false;
+            }
+        }
+        stop
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn status(round: usize, best_fitness: f64) -> Status {
+        Status {
+            round: round,
+            evaluations: 0,
+            best_fitness: best_fitness,
+            elapsed: Duration::from_secs(0),
+        }
+    }
+
+    #[test]
+    fn stagnation_resets_on_improvement() {
+        let mut condition = stagnation(2);
+        assert!(!condition.should_stop(&status(0, 1.0)));
+        assert!(!condition.should_stop(&status(1, 1.0)));
+        assert!(!condition.should_stop(&status(2, 2.0)));
+        assert!(!condition.should_stop(&status(3, 2.0)));
+        assert!(condition.should_stop(&status(4, 2.0)));
+    }
+
+    #[test]
+    fn combinators() {
+        let mut either = any(vec![target_fitness(5.0), max_rounds(3)]);
+        assert!(!either.should_stop(&status(0, 1.0)));
+        assert!(either.should_stop(&status(0, 5.0)));
+        assert!(either.should_stop(&status(3, 1.0)));
+
+        let mut both = all(vec![target_fitness(5.0), max_rounds(3)]);
+        assert!(!both.should_stop(&status(0, 5.0)));
+        assert!(!both.should_stop(&status(3, 1.0)));
+        assert!(both.should_stop(&status(3, 5.0)));
+    }
+}
@@ -40,6 +40,28 @@ impl TaskGenerator {
     pub fn stop(&mut self) {
         self.stopped = true;
     }
+
+    /// Claims every task in the next round at once, returning the round's
+    /// number, or `None` if the generator has stopped or run out of rounds.
+    pub fn next_round(&mut self) -> Option<usize> {
+        if let Some(n) = self.max_rounds {
+            if self.round >= n {
+                self.stopped = true;
+            }
+        }
+        if self.stopped {
+            return None;
+        }
+
+        let round = self.round;
+        self.round += 1;
+        if let Some(n) = self.max_rounds {
+            if self.round >= n {
+                self.stopped = true;
+            }
+        }
+        Some(round)
+    }
 }
 
 impl Iterator for TaskGenerator {
@@ -100,4 +122,18 @@ mod tests {
         assert_eq!(gathered.len(), expected.len());
         assert!(gathered.iter().zip(expected.iter()).all(|(x, y)| *x == *y));
     }
+
+    #[test]
+    fn whole_rounds() {
+        use super::*;
+        let mut tg = TaskGenerator::new(3, 2).max_rounds(2);
+        assert_eq!(tg.next_round(), Some(0));
+        assert_eq!(tg.next_round(), Some(1));
+        assert_eq!(tg.next_round(), None);
+        assert_eq!(tg.round, 2);
+
+        let mut none = TaskGenerator::new(3, 2).max_rounds(0);
+        assert_eq!(none.next_round(), None);
+        assert_eq!(none.round, 0);
+    }
 }
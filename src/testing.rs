@@ -0,0 +1,67 @@
+//! Contexts and moves shared by the unit tests.
+
+extern crate rand;
+
+use self::rand::{thread_rng, Rng};
+
+use candidate::Candidate;
+use context::{Context, HiveRng};
+
+/// Implements `make` and `explore` by way of `make_with_rng` and
+/// `explore_with_rng`, with a generator seeded from the thread's.
+macro_rules! thread_rng_moves {
+    ($solution:ty) => {
+        fn make(&self) -> $solution {
+            self.make_with_rng(&mut ::testing::thread_hive_rng())
+        }
+
+        fn explore(&self, field: &[::candidate::Candidate<$solution>], index: usize) -> $solution {
+            self.explore_with_rng(field, index, &mut ::testing::thread_hive_rng())
+        }
+    }
+}
+
+/// Implements the moves of a walk over the integers: solutions start
+/// between 0 and 100, and step up to 10 either way.
+macro_rules! walk_moves {
+    () => {
+        thread_rng_moves!(i32);
+
+        fn make_with_rng(&self, rng: &mut ::context::HiveRng) -> i32 {
+            ::testing::start(rng)
+        }
+
+        fn explore_with_rng(&self,
+                            field: &[::candidate::Candidate<i32>],
+                            index: usize,
+                            rng: &mut ::context::HiveRng)
+                            -> i32 {
+            ::testing::step(field, index, rng)
+        }
+    }
+}
+
+pub fn thread_hive_rng() -> HiveRng {
+    thread_rng().gen()
+}
+
+pub fn start(rng: &mut HiveRng) -> i32 {
+    rng.gen_range(0, 100)
+}
+
+pub fn step(field: &[Candidate<i32>], index: usize, rng: &mut HiveRng) -> i32 {
+    field[index].solution + rng.gen_range(-10, 10)
+}
+
+/// Walks over the integers, looking for the largest.
+pub struct Walk;
+
+impl Context for Walk {
+    type Solution = i32;
+
+    walk_moves!();
+
+    fn evaluate_fitness(&self, solution: &i32) -> f64 {
+        *solution as f64
+    }
+}